@@ -0,0 +1,144 @@
+// Card animations, like the hard drive analysis in game: a scan, then the cards slide in one after the other.
+// Everything advances with the frame time so it lasts the same at any frame rate.
+
+use macroquad::prelude::*;
+
+const SCAN_TIME: f32 = 1.0;
+const SLIDE_TIME: f32 = 0.45;
+// Delay between two cards starting to slide
+const STAGGER: f32 = 0.12;
+const HOVER_TIME: f32 = 0.12;
+const HOVER_SCALE: f32 = 1.04;
+const FLASH_TIME: f32 = 0.4;
+const CARDS: usize = 3;
+
+pub fn ease_out_cubic(t: f32) -> f32 {
+    1.0 - (1.0 - t.clamp(0.0, 1.0)).powi(3)
+}
+
+pub fn ease_in_out_quad(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 { 2.0 * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0 }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardAnimation {
+    // Since the current draw started showing
+    elapsed: f32,
+    // Eased towards 1 while the card is hovered
+    hover: [f32; CARDS],
+    // Card confirmed and time since
+    flash: Option<(usize, f32)>,
+}
+
+impl Default for CardAnimation {
+    fn default() -> Self {
+        CardAnimation { elapsed: 0.0, hover: [0.0; CARDS], flash: None }
+    }
+}
+
+impl CardAnimation {
+    // A new draw, scanned again
+    pub fn restart(&mut self) {
+        *self = CardAnimation::default();
+    }
+
+    // Call once per frame with get_frame_time()
+    pub fn update(&mut self, dt: f32, hovered: Option<usize>) {
+        self.elapsed += dt;
+        for (i, hover) in self.hover.iter_mut().enumerate() {
+            let step = dt / HOVER_TIME;
+            *hover = if hovered == Some(i) { (*hover + step).min(1.0) } else { (*hover - step).max(0.0) };
+        }
+        if let Some((_, t)) = &mut self.flash {
+            *t += dt;
+        }
+    }
+
+    // Progress of the scan from 0 to 1, None once done
+    pub fn scanning(&self) -> Option<f32> {
+        if self.elapsed < SCAN_TIME { Some(self.elapsed / SCAN_TIME) } else { None }
+    }
+
+    // 0 before the card starts sliding in, 1 once in place
+    pub fn reveal(&self, i: usize) -> f32 {
+        ease_out_cubic((self.elapsed - SCAN_TIME - STAGGER * i as f32) / SLIDE_TIME)
+    }
+
+    pub fn revealed(&self, count: usize) -> bool {
+        (0..count).all(|i| self.reveal(i) >= 1.0)
+    }
+
+    // Where to draw a card that sits in `rect`: coming from the bottom of the screen, then grown when hovered
+    pub fn card_rect(&self, i: usize, rect: Rect) -> Rect {
+        let offset = (1.0 - self.reveal(i)) * (screen_height() - rect.y);
+        let scale = 1.0 + (HOVER_SCALE - 1.0) * ease_in_out_quad(self.hover.get(i).copied().unwrap_or(0.0));
+        let (w, h) = (rect.w * scale, rect.h * scale);
+        Rect::new(rect.x - (w - rect.w) / 2.0, rect.y - (h - rect.h) / 2.0 + offset, w, h)
+    }
+
+    pub fn confirm(&mut self, i: usize) {
+        self.flash = Some((i, 0.0));
+    }
+
+    // Strength of the confirmation flash on a card, from 1 down to 0
+    pub fn flash(&self, i: usize) -> f32 {
+        match self.flash {
+            Some((card, t)) if card == i => 1.0 - ease_out_cubic(t / FLASH_TIME),
+            _ => 0.0,
+        }
+    }
+
+    // The next draw waits for the flash to end
+    pub fn flashing(&self) -> bool {
+        self.flash.is_some_and(|(_, t)| t < FLASH_TIME)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(fps: u32, seconds: f32) -> CardAnimation {
+        let mut anim = CardAnimation::default();
+        let frames = (seconds * fps as f32).round() as u32;
+        for frame in 0..frames {
+            anim.update(1.0 / fps as f32, if frame < frames / 2 { Some(1) } else { None });
+            if frame == frames / 2 {
+                anim.confirm(1);
+            }
+        }
+        anim
+    }
+
+    #[test]
+    fn same_at_any_frame_rate() {
+        let (slow, fast) = (run(30, 1.5), run(144, 1.5));
+        assert!((slow.elapsed - fast.elapsed).abs() < 1e-3);
+        for i in 0..CARDS {
+            assert!((slow.reveal(i) - fast.reveal(i)).abs() < 1e-3);
+            assert!((slow.hover[i] - fast.hover[i]).abs() < 1e-3);
+            assert!((slow.flash(i) - fast.flash(i)).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn phases() {
+        let mut anim = CardAnimation::default();
+        assert_eq!(anim.scanning(), Some(0.0));
+        assert_eq!(anim.reveal(0), 0.0);
+        anim.update(SCAN_TIME + SLIDE_TIME, None);
+        assert_eq!(anim.scanning(), None);
+        assert_eq!(anim.reveal(0), 1.0);
+        assert!(!anim.revealed(3));
+        anim.update(STAGGER * 2.0, None);
+        assert!(anim.revealed(3));
+
+        anim.confirm(2);
+        assert!(anim.flashing());
+        assert_eq!(anim.flash(2), 1.0);
+        assert_eq!(anim.flash(0), 0.0);
+        anim.update(FLASH_TIME, None);
+        assert!(!anim.flashing());
+    }
+}
@@ -0,0 +1,213 @@
+// Extracts the alternate recipes from a saved copy of https://satisfactory.fandom.com/wiki/Hard_Drive
+// cargo run --no-default-features --features scrape --bin scrape -- Hard_Drive.html [res/recipes.json] > recipes.json
+// The table only has the recipe, product, ingredients and rate: building, unlocks and duration come from
+// the recipes we already know (res/recipes.json by default), or from extra columns when the page has them.
+
+use scraper::{Html, Selector, ElementRef};
+
+use satisfactory_alt_recipe::items::*;
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("Usage: scrape <Hard_Drive.html> [known recipes, default res/recipes.json]");
+        std::process::exit(2);
+    };
+    let known_path = std::env::args().nth(2).unwrap_or("res/recipes.json".to_string());
+    let known: Vec<Recipe> = match std::fs::read_to_string(&known_path).map_err(|e| e.to_string()).and_then(|t| serde_json::from_str(&t).map_err(|e| e.to_string())) {
+        Ok(known) => known,
+        Err(e) => {
+            eprintln!("Unable to load {}: {}", known_path, e);
+            std::process::exit(1);
+        },
+    };
+    let page = match std::fs::read_to_string(&path) {
+        Ok(page) => page,
+        Err(e) => {
+            eprintln!("Unable to read {}: {}", path, e);
+            std::process::exit(1);
+        },
+    };
+
+    let (recipes, errors) = scrape(&page, &known);
+    for e in &errors {
+        eprintln!("{}", e);
+    }
+    println!("{}", serde_json::to_string(&recipes).unwrap());
+    if !errors.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+// Rows that can't be parsed are reported and left out
+fn scrape(page: &str, known: &[Recipe]) -> (Vec<Recipe>, Vec<String>) {
+    let document = Html::parse_document(page);
+    let sel = Selector::parse("#alternateRecipesTable").unwrap();
+    let Some(table) = document.select(&sel).next() else {
+        return (Vec::new(), vec!["No #alternateRecipesTable in the page".to_string()]);
+    };
+    let row_sel = Selector::parse("tr").unwrap();
+
+    let mut recipes = Vec::new();
+    let mut errors = Vec::new();
+    for (i, row) in table.select(&row_sel).enumerate() {
+        match parse_line(row, known) {
+            Ok(Some(recipe)) => recipes.push(recipe),
+            Ok(None) => {},
+            Err(e) => errors.push(format!("Row {}: {}", i, e)),
+        }
+    }
+    (recipes, errors)
+}
+
+// Columns are: recipe, product, ingredients (name then amount), rate,
+// then optionally building and unlocked by ("Tier 2, Caterium")
+fn parse_line(row: ElementRef, known: &[Recipe]) -> Result<Option<Recipe>, String> {
+    let col_sel = Selector::parse("td").unwrap();
+    let cols : Vec<ElementRef> = row.select(&col_sel).collect();
+    if cols.is_empty() {
+        // Header
+        return Ok(None);
+    }
+    if cols.len() < 4 {
+        return Err(format!("Expected at least 4 columns, found {}", cols.len()));
+    }
+
+    let text = |col: &ElementRef| -> Vec<String> {
+        col.text().map(|t| t.trim()).filter(|t| !t.is_empty()).map(|t| t.to_string()).collect()
+    };
+    let number = |s: &str, what: &str| -> Result<f32, String> {
+        s.parse::<f32>().map_err(|_| format!("Invalid {} \"{}\"", what, s))
+    };
+
+    let name = text(&cols[0]).join(" ");
+    if name.is_empty() {
+        return Err("Missing recipe name".to_string());
+    }
+    let product = text(&cols[1]).join(" ");
+    if product.is_empty() {
+        return Err(format!("{}: missing product", name));
+    }
+    let product = Item::try_from(product).map_err(|e| format!("{}: {}", name, e))?;
+
+    let ingredients = text(&cols[2]);
+    if ingredients.is_empty() || ingredients.len() % 2 != 0 {
+        return Err(format!("{}: ingredients should be name and amount pairs", name));
+    }
+    let mut input = Vec::new();
+    for pair in ingredients.chunks(2) {
+        input.push(Ingredients {
+            name: Item::try_from(pair[0].clone()).map_err(|e| format!("{}: {}", name, e))?,
+            nb: number(&pair[1], "amount").map_err(|e| format!("{}: {}", name, e))?,
+        });
+    }
+
+    let rate = number(&text(&cols[3]).join(""), "rate").map_err(|e| format!("{}: {}", name, e))?;
+
+    let previous = known.iter().find(|r| r.name == name);
+    let (building, tier, research) = if cols.len() >= 6 {
+        parse_unlocks(&text(&cols[4]).join(" "), &text(&cols[5]).join(" ")).map_err(|e| format!("{}: {}", name, e))?
+    } else {
+        let previous = previous.ok_or(format!("{}: not in the known recipes, add its building and tier by hand", name))?;
+        (previous.building, previous.tier, previous.research.clone())
+    };
+
+    Ok(Some(Recipe {
+        name,
+        building,
+        tier,
+        research,
+        // Not in the table
+        duration: previous.and_then(|r| r.duration),
+        input,
+        output: vec![Ingredients { name: product, nb: rate }],
+    }))
+}
+
+fn parse_unlocks(building_name: &str, unlocked_by: &str) -> Result<(Building, u8, Vec<String>), String> {
+    let building: Building = serde_json::from_value(serde_json::Value::String(building_name.to_string()))
+        .map_err(|_| format!("unknown building \"{}\"", building_name))?;
+
+    let mut tier = None;
+    let mut research = Vec::new();
+    for prerequisite in unlocked_by.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        if let Some(t) = prerequisite.strip_prefix("Tier ") {
+            tier = Some(t.parse::<u8>().map_err(|_| format!("invalid tier \"{}\"", t))?);
+        } else if RESEARCHES.contains(&prerequisite) {
+            research.push(prerequisite.to_string());
+        } else {
+            return Err(format!("unknown prerequisite \"{}\"", prerequisite));
+        }
+    }
+    let tier = tier.ok_or("missing tier")?;
+    Ok((building, tier, research))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = include_str!("../../tests/fixtures/hard_drive.html");
+
+    fn known() -> Vec<Recipe> {
+        serde_json::from_str(include_str!("../../res/recipes.json")).unwrap()
+    }
+
+    #[test]
+    fn parses_hard_drive_table() {
+        let (recipes, _) = scrape(PAGE, &known());
+        assert_eq!(recipes.len(), 4);
+
+        let fused_wire = &recipes[1];
+        assert_eq!(fused_wire.name, "Fused Wire");
+        assert_eq!(fused_wire.input.len(), 2);
+        assert_eq!(fused_wire.input[1].name.name(), "Caterium Ingot");
+        assert_eq!(fused_wire.input[1].nb, 3.0);
+        assert_eq!(fused_wire.product().name.name(), "Wire");
+        assert_eq!(fused_wire.product().nb, 90.0);
+        // From the known recipes
+        assert_eq!(fused_wire.building, Building::Assembler);
+        assert_eq!(fused_wire.tier, 2);
+        assert_eq!(fused_wire.research, vec!["Caterium".to_string()]);
+        assert_eq!(fused_wire.duration, Some(20.0));
+
+        let turbo = &recipes[3];
+        assert_eq!(turbo.building, Building::Blender);
+        assert_eq!(turbo.input.len(), 4);
+        assert_eq!(turbo.input[3].nb, 22.5);
+    }
+
+    #[test]
+    fn reports_bad_rows() {
+        let (_, errors) = scrape(PAGE, &known());
+        assert_eq!(errors, vec!["Row 5: Unfinished Recipe: Invalid amount \"?\"".to_string()]);
+    }
+
+    #[test]
+    fn unknown_recipes_need_their_building() {
+        let (recipes, errors) = scrape(PAGE, &[]);
+        assert!(recipes.is_empty());
+        assert_eq!(errors[0], "Row 1: Cast Screw: not in the known recipes, add its building and tier by hand");
+    }
+
+    #[test]
+    fn building_and_unlock_columns() {
+        let page = "<table id=\"alternateRecipesTable\">\
+            <tr><th>Recipe</th><th>Product</th><th>Ingredients</th><th>Rate</th><th>Building</th><th>Unlocked by</th></tr>\
+            <tr><td>Fused Wire</td><td>Wire</td><td>Copper Ingot<br>12<br>Caterium Ingot<br>3</td><td>90</td><td>Assembler</td><td>Tier 3, Caterium</td></tr>\
+            <tr><td>Cast Screw</td><td>Screw</td><td>Iron Ingot<br>12.5</td><td>50</td><td>Constructor</td><td>Caterium</td></tr>\
+            </table>";
+        let (recipes, errors) = scrape(page, &[]);
+        assert_eq!(recipes.len(), 1);
+        assert_eq!((recipes[0].building, recipes[0].tier), (Building::Assembler, 3));
+        assert_eq!(recipes[0].research, vec!["Caterium".to_string()]);
+        assert_eq!(recipes[0].duration, None);
+        assert_eq!(errors, vec!["Row 2: Cast Screw: missing tier".to_string()]);
+    }
+
+    #[test]
+    fn missing_table() {
+        let (recipes, errors) = scrape("<html><body></body></html>", &[]);
+        assert!(recipes.is_empty());
+        assert_eq!(errors.len(), 1);
+    }
+}
@@ -0,0 +1,225 @@
+// Compares two recipe files, to see what a game update changed in the alternates
+
+use serde::Serialize;
+
+use crate::items::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Input,
+    Output,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Change {
+    Building { old: Building, new: Building },
+    Tier { old: u8, new: u8 },
+    Research { old: Vec<String>, new: Vec<String> },
+    Duration { old: Option<f32>, new: Option<f32> },
+    // Amount per minute, None when the item isn't in that version of the recipe
+    Item { side: Side, item: String, old: Option<f32>, new: Option<f32> },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Modified {
+    pub name: String,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RecipeDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<Modified>,
+}
+
+pub fn load(path: &str) -> Result<Vec<Recipe>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Unable to parse {}: {}", path, e))
+}
+
+impl RecipeDiff {
+    // Recipes are matched by name
+    pub fn new(old: &[Recipe], new: &[Recipe]) -> Self {
+        let mut result = RecipeDiff::default();
+        for recipe in new {
+            match old.iter().find(|r| r.name == recipe.name) {
+                Some(previous) => {
+                    let changes = recipe_changes(previous, recipe);
+                    if !changes.is_empty() {
+                        result.modified.push(Modified { name: recipe.name.clone(), changes });
+                    }
+                },
+                None => result.added.push(recipe.name.clone()),
+            }
+        }
+        result.removed = old.iter().filter(|r| new.iter().all(|n| n.name != r.name)).map(|r| r.name.clone()).collect();
+        result
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    pub fn to_text(&self) -> String {
+        if self.is_empty() {
+            return "No changes\n".to_string();
+        }
+        let mut result = String::new();
+        if !self.added.is_empty() {
+            result += &format!("Added ({}):\n", self.added.len());
+            for name in &self.added {
+                result += &format!("  + {}\n", name);
+            }
+        }
+        if !self.removed.is_empty() {
+            result += &format!("Removed ({}):\n", self.removed.len());
+            for name in &self.removed {
+                result += &format!("  - {}\n", name);
+            }
+        }
+        if !self.modified.is_empty() {
+            result += &format!("Modified ({}):\n", self.modified.len());
+            for recipe in &self.modified {
+                result += &format!("  ~ {}\n", recipe.name);
+                for change in &recipe.changes {
+                    result += &format!("      {}\n", change_text(change));
+                }
+            }
+        }
+        result
+    }
+}
+
+fn recipe_changes(old: &Recipe, new: &Recipe) -> Vec<Change> {
+    let mut result = Vec::new();
+    if old.building != new.building {
+        result.push(Change::Building { old: old.building, new: new.building });
+    }
+    if old.tier != new.tier {
+        result.push(Change::Tier { old: old.tier, new: new.tier });
+    }
+    if old.research != new.research {
+        result.push(Change::Research { old: old.research.clone(), new: new.research.clone() });
+    }
+    if old.duration != new.duration {
+        result.push(Change::Duration { old: old.duration, new: new.duration });
+    }
+    result.extend(item_changes(Side::Input, &old.input, &new.input));
+    result.extend(item_changes(Side::Output, &old.output, &new.output));
+    result
+}
+
+fn item_changes(side: Side, old: &[Ingredients], new: &[Ingredients]) -> Vec<Change> {
+    let amount = |list: &[Ingredients], name: &str| list.iter().find(|i| i.name.name() == name).map(|i| i.nb);
+
+    let mut names: Vec<&str> = new.iter().map(|i| i.name.name()).collect();
+    names.extend(old.iter().map(|i| i.name.name()).filter(|n| amount(new, n).is_none()));
+
+    names.into_iter().filter_map(|name| {
+        let (before, after) = (amount(old, name), amount(new, name));
+        if before == after {
+            None
+        } else {
+            Some(Change::Item { side, item: name.to_string(), old: before, new: after })
+        }
+    }).collect()
+}
+
+fn change_text(change: &Change) -> String {
+    match change {
+        Change::Building { old, new } => format!("building: {} -> {}", old.name(), new.name()),
+        Change::Tier { old, new } => format!("tier: {} -> {}", old, new),
+        Change::Research { old, new } => format!("research: [{}] -> [{}]", old.join(", "), new.join(", ")),
+        Change::Duration { old, new } => {
+            let seconds = |d: &Option<f32>| d.map_or("unknown".to_string(), |d| format!("{}s", d));
+            format!("duration: {} -> {}", seconds(old), seconds(new))
+        },
+        Change::Item { side, item, old, new } => {
+            let side = match side {
+                Side::Input => "input",
+                Side::Output => "output",
+            };
+            match (old, new) {
+                (Some(old), Some(new)) => format!("{} {}: {}/min -> {}/min", side, item, old, new),
+                (None, Some(new)) => format!("{} {}: added ({}/min)", side, item, new),
+                (Some(old), None) => format!("{} {}: removed ({}/min)", side, item, old),
+                (None, None) => unreachable!(),
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions() -> (Vec<Recipe>, Vec<Recipe>) {
+        let old = vec![
+            Recipe::test("Cast Screw", &[("Iron Ingot", 12.5)], &[("Screw", 50.0)]),
+            Recipe::test("Iron Wire", &[("Iron Ingot", 12.5)], &[("Wire", 22.5)]),
+            Recipe { tier: 2, ..Recipe::test("Bolted Frame", &[("Reinforced Iron Plate", 7.5), ("Screw", 140.0)], &[("Modular Frame", 5.0)]) },
+        ];
+        let mut bolted_frame = old[2].clone();
+        bolted_frame.tier = 3;
+        bolted_frame.input[1].nb = 100.0;
+        bolted_frame.input.push(Ingredients { name: "Iron Rod".to_string().try_into().unwrap(), nb: 10.0 });
+        bolted_frame.input.remove(0);
+        let new = vec![
+            old[0].clone(),
+            bolted_frame,
+            Recipe::test("Fused Wire", &[("Copper Ingot", 12.0), ("Caterium Ingot", 3.0)], &[("Wire", 90.0)]),
+        ];
+        (old, new)
+    }
+
+    #[test]
+    fn added_removed_modified() {
+        let (old, new) = versions();
+        let diff = RecipeDiff::new(&old, &new);
+        assert_eq!(diff.added, vec!["Fused Wire"]);
+        assert_eq!(diff.removed, vec!["Iron Wire"]);
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(diff.modified[0].name, "Bolted Frame");
+        assert_eq!(diff.modified[0].changes, vec![
+            Change::Tier { old: 2, new: 3 },
+            Change::Item { side: Side::Input, item: "Screw".to_string(), old: Some(140.0), new: Some(100.0) },
+            Change::Item { side: Side::Input, item: "Iron Rod".to_string(), old: None, new: Some(10.0) },
+            Change::Item { side: Side::Input, item: "Reinforced Iron Plate".to_string(), old: Some(7.5), new: None },
+        ]);
+    }
+
+    #[test]
+    fn unchanged() {
+        let (old, _) = versions();
+        let diff = RecipeDiff::new(&old, &old);
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_text(), "No changes\n");
+    }
+
+    #[test]
+    fn text_and_json() {
+        let (old, new) = versions();
+        let diff = RecipeDiff::new(&old, &new);
+        assert_eq!(diff.to_text(), "\
+Added (1):
+  + Fused Wire
+Removed (1):
+  - Iron Wire
+Modified (1):
+  ~ Bolted Frame
+      tier: 2 -> 3
+      input Screw: 140/min -> 100/min
+      input Iron Rod: added (10/min)
+      input Reinforced Iron Plate: removed (7.5/min)
+");
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["added"], serde_json::json!(["Fused Wire"]));
+        assert_eq!(json["removed"], serde_json::json!(["Iron Wire"]));
+        assert_eq!(json["modified"][0]["changes"][0], serde_json::json!({ "kind": "tier", "old": 2, "new": 3 }));
+        assert_eq!(json["modified"][0]["changes"][2], serde_json::json!({ "kind": "item", "side": "input", "item": "Iron Rod", "old": null, "new": 10.0 }));
+    }
+}
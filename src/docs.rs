@@ -0,0 +1,274 @@
+// Imports the alternate recipes from the Docs.json shipped with the game (CommunityResources/Docs/Docs.json)
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::items::*;
+
+struct Descriptor {
+    name: String,
+    // Fluid amounts are stored in liters
+    fluid: bool,
+}
+
+// Milestone tier and MAM research that unlocks something
+#[derive(Debug, Clone, PartialEq)]
+struct Unlock {
+    tier: u8,
+    research: Option<String>,
+}
+
+pub fn import(path: &str) -> Result<Vec<Recipe>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Unable to read {}: {}", path, e))?;
+    let text = decode(&bytes)?;
+    let docs: Value = serde_json::from_str(&text).map_err(|e| format!("Unable to parse {}: {}", path, e))?;
+    parse(&docs)
+}
+
+// Docs.json is UTF-16 with a BOM, accept UTF-8 too in case it was converted
+fn decode(bytes: &[u8]) -> Result<String, String> {
+    let utf16 = |be: bool| -> Result<String, String> {
+        let units: Vec<u16> = bytes[2..].chunks_exact(2).map(|c| if be { u16::from_be_bytes([c[0], c[1]]) } else { u16::from_le_bytes([c[0], c[1]]) }).collect();
+        String::from_utf16(&units).map_err(|e| format!("Invalid UTF-16: {}", e))
+    };
+    match bytes {
+        [0xFF, 0xFE, ..] => utf16(false),
+        [0xFE, 0xFF, ..] => utf16(true),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8(rest.to_vec()).map_err(|e| format!("Invalid UTF-8: {}", e)),
+        _ => String::from_utf8(bytes.to_vec()).map_err(|e| format!("Invalid UTF-8: {}", e)),
+    }
+}
+
+fn parse(docs: &Value) -> Result<Vec<Recipe>, String> {
+    let groups = docs.as_array().ok_or("Docs.json should be an array of native classes")?;
+
+    let mut descriptors: HashMap<String, Descriptor> = HashMap::new();
+    let mut recipes: Vec<&Value> = Vec::new();
+    let mut schematics: Vec<&Value> = Vec::new();
+    for group in groups {
+        let native_class = group["NativeClass"].as_str().unwrap_or_default();
+        for class in group["Classes"].as_array().into_iter().flatten() {
+            if native_class.ends_with(".FGRecipe'") {
+                recipes.push(class);
+            } else if native_class.ends_with(".FGSchematic'") {
+                schematics.push(class);
+            } else if let (Some(class_name), Some(name)) = (class["ClassName"].as_str(), class["mDisplayName"].as_str()) {
+                let form = class["mForm"].as_str().unwrap_or_default();
+                descriptors.insert(class_name.to_string(), Descriptor { name: name.to_string(), fluid: form == "RF_LIQUID" || form == "RF_GAS" });
+            }
+        }
+    }
+
+    // What unlocks each recipe
+    let mut recipe_unlocks: HashMap<String, Unlock> = HashMap::new();
+    for schematic in schematics {
+        let Some(unlock) = schematic_unlock(schematic) else { continue };
+        for unlock_group in schematic["mUnlocks"].as_array().into_iter().flatten() {
+            let Some(list) = unlock_group["mRecipes"].as_str() else { continue };
+            for recipe in class_names(list) {
+                let entry = recipe_unlocks.entry(recipe).or_insert(unlock.clone());
+                if is_earlier(&unlock, entry) {
+                    *entry = unlock.clone();
+                }
+            }
+        }
+    }
+
+    // An item (or building) is available as soon as a standard recipe making it is
+    let mut item_unlocks: HashMap<String, Unlock> = HashMap::new();
+    for recipe in recipes.iter().filter(|r| !is_alternate(r)) {
+        let Some(unlock) = recipe_unlocks.get(recipe["ClassName"].as_str().unwrap_or_default()) else { continue };
+        for (item, _) in item_amounts(recipe["mProduct"].as_str().unwrap_or_default()) {
+            let entry = item_unlocks.entry(item).or_insert(unlock.clone());
+            if is_earlier(unlock, entry) {
+                *entry = unlock.clone();
+            }
+        }
+    }
+
+    let mut result = Vec::new();
+    for recipe in recipes.iter().filter(|r| is_alternate(r)) {
+        let class_name = recipe["ClassName"].as_str().unwrap_or_default();
+        let Some((building, building_class)) = producer(recipe["mProducedIn"].as_str().unwrap_or_default()) else {
+            // Made in the workshop or the build gun
+            continue;
+        };
+        let duration: f32 = recipe["mManufactoringDuration"].as_str().and_then(|d| d.parse().ok()).unwrap_or(0.0);
+        if duration <= 0.0 {
+            return Err(format!("{} has no duration", class_name));
+        }
+
+        let to_ingredients = |list: &str| -> Result<Vec<Ingredients>, String> {
+            item_amounts(list).into_iter().map(|(item, amount)| {
+                let descriptor = descriptors.get(&item).ok_or(format!("{} uses unknown item {}", class_name, item))?;
+                let amount = if descriptor.fluid { amount / 1000.0 } else { amount };
+                let per_minute = (amount * 60.0 / duration * 10.0).round() / 10.0;
+                let item = Item::try_from(descriptor.name.clone()).map_err(|e| format!("{}: {}", class_name, e))?;
+                Ok(Ingredients { name: item, nb: per_minute })
+            }).collect()
+        };
+        let input = to_ingredients(recipe["mIngredients"].as_str().unwrap_or_default())?;
+        let output = to_ingredients(recipe["mProduct"].as_str().unwrap_or_default())?;
+        if output.is_empty() {
+            return Err(format!("{} has no product", class_name));
+        }
+
+        // Needs the building and every item to be available
+        let building_item = building_class.replacen("Build_", "Desc_", 1);
+        let items = item_amounts(recipe["mIngredients"].as_str().unwrap_or_default()).into_iter()
+            .chain(item_amounts(recipe["mProduct"].as_str().unwrap_or_default()))
+            .map(|(item, _)| item)
+            .chain(std::iter::once(building_item));
+        let mut tier = 0;
+        let mut research: Vec<String> = Vec::new();
+        for unlock in items.filter_map(|i| item_unlocks.get(&i)) {
+            tier = tier.max(unlock.tier);
+            if let Some(r) = &unlock.research {
+                if !research.contains(r) {
+                    research.push(r.clone());
+                }
+            }
+        }
+        research.sort_by_key(|r| RESEARCHES.iter().position(|known| known == r));
+
+        let name = recipe["mDisplayName"].as_str().unwrap_or(class_name);
+        result.push(Recipe {
+            name: name.trim_start_matches("Alternate:").trim().to_string(),
+            building,
+            tier,
+            research,
+            duration: Some(duration),
+            input,
+            output,
+        });
+    }
+    Ok(result)
+}
+
+fn is_alternate(recipe: &Value) -> bool {
+    recipe["ClassName"].as_str().unwrap_or_default().starts_with("Recipe_Alternate_")
+}
+
+// Milestones give their tier, MAM researches we know about their tree. Everything else (hard drives, AWESOME shop...) is ignored.
+fn schematic_unlock(schematic: &Value) -> Option<Unlock> {
+    let tier = schematic["mTechTier"].as_str().and_then(|t| t.parse().ok()).unwrap_or(0);
+    match schematic["mType"].as_str()? {
+        "EST_Milestone" => Some(Unlock { tier, research: None }),
+        "EST_Tutorial" | "EST_Custom" => Some(Unlock { tier: 0, research: None }),
+        "EST_MAM" => {
+            // Research_Caterium_3_C -> Caterium
+            let tree = schematic["ClassName"].as_str()?.split('_').nth(1)?;
+            RESEARCHES.iter().find(|r| **r == tree).map(|r| Unlock { tier: 0, research: Some(r.to_string()) })
+        },
+        _ => None,
+    }
+}
+
+// Milestones are preferred over researches, then lower tiers
+fn is_earlier(a: &Unlock, b: &Unlock) -> bool {
+    (a.research.is_some(), a.tier) < (b.research.is_some(), b.tier)
+}
+
+fn producer(list: &str) -> Option<(Building, String)> {
+    class_names(list).into_iter().find_map(|class| {
+        let building = match class.as_str() {
+            "Build_SmelterMk1_C" => Building::Smelter,
+            "Build_FoundryMk1_C" => Building::Foundry,
+            "Build_ConstructorMk1_C" => Building::Constructor,
+            "Build_AssemblerMk1_C" => Building::Assembler,
+            "Build_ManufacturerMk1_C" => Building::Manufacturer,
+            "Build_OilRefinery_C" => Building::Refinery,
+            "Build_Blender_C" => Building::Blender,
+            "Build_Packager_C" => Building::Packager,
+            "Build_HadronCollider_C" => Building::ParticleAccelerator,
+            _ => return None,
+        };
+        Some((building, class))
+    })
+}
+
+// (/Game/A/B.B_C,"/Game/C/D.D_C") -> [B_C, D_C]
+fn class_names(list: &str) -> Vec<String> {
+    list.split(',')
+        .filter_map(|path| path.rsplit('.').next())
+        .map(|class| class.trim_matches(['(', ')', '"', '\'']).to_string())
+        .filter(|class| class.ends_with("_C"))
+        .collect()
+}
+
+// ((ItemClass=BlueprintGeneratedClass'"/Game/.../Desc_IronPlate.Desc_IronPlate_C"',Amount=6),(...)) -> [(Desc_IronPlate_C, 6)]
+fn item_amounts(list: &str) -> Vec<(String, f32)> {
+    list.split("ItemClass=").skip(1).filter_map(|entry| {
+        let (class, rest) = entry.split_once(",Amount=")?;
+        let class = class_names(class).pop()?;
+        let amount = rest.split([')', ',']).next()?.parse().ok()?;
+        Some((class, amount))
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cut down Docs.json, saved in UTF-16 like the game does
+    const FIXTURE: &str = "tests/fixtures/docs.json";
+
+    #[test]
+    fn imports_alternates() {
+        let recipes = import(FIXTURE).unwrap();
+        let names: Vec<&str> = recipes.iter().map(|r| r.name.as_str()).collect();
+        // The workshop one is left out
+        assert_eq!(names, vec!["Cast Screw", "Pure Iron Ingot", "Fused Wire"]);
+
+        let screw = &recipes[0];
+        assert_eq!(screw.building, Building::Constructor);
+        assert_eq!((screw.tier, screw.research.len()), (0, 0));
+        assert_eq!(screw.duration, Some(24.0));
+        assert_eq!((screw.input[0].name.name(), screw.input[0].nb), ("Iron Ingot", 12.5));
+        assert_eq!((screw.output[0].name.name(), screw.output[0].nb), ("Screw", 50.0));
+
+        // Water is in liters in the file
+        let iron = &recipes[1];
+        assert_eq!(iron.building, Building::Refinery);
+        assert_eq!(iron.tier, 5);
+        assert_eq!((iron.input[1].name.name(), iron.input[1].nb), ("Water", 20.0));
+        assert_eq!(iron.output[0].nb, 65.0);
+
+        let wire = &recipes[2];
+        assert_eq!(wire.tier, 2);
+        assert_eq!(wire.research, vec!["Caterium".to_string()]);
+    }
+
+    #[test]
+    fn decodes_text() {
+        let utf16: Vec<u8> = [0xFF, 0xFE].into_iter().chain("[é]".encode_utf16().flat_map(|u| u.to_le_bytes())).collect();
+        assert_eq!(decode(&utf16).unwrap(), "[é]");
+        let utf16_be: Vec<u8> = [0xFE, 0xFF].into_iter().chain("[é]".encode_utf16().flat_map(|u| u.to_be_bytes())).collect();
+        assert_eq!(decode(&utf16_be).unwrap(), "[é]");
+        assert_eq!(decode(&[0xEF, 0xBB, 0xBF, b'[', b']']).unwrap(), "[]");
+        assert_eq!(decode(b"[]").unwrap(), "[]");
+        assert!(decode(&[0xFF, 0xFE, 0x00, 0xD8]).is_err());
+    }
+
+    #[test]
+    fn parses_class_lists() {
+        assert_eq!(class_names("(\"/Game/A/B.B_C\",\"/Game/C/D.D_C\")"), vec!["B_C", "D_C"]);
+        assert_eq!(class_names("(/Game/A/B.B_C,/Game/C/D.Other)"), vec!["B_C"]);
+        assert!(class_names("").is_empty());
+
+        let amounts = item_amounts("((ItemClass=BlueprintGeneratedClass'\"/Game/P/Desc_IronPlate.Desc_IronPlate_C\"',Amount=6),(ItemClass=BlueprintGeneratedClass'\"/Game/P/Desc_Screw.Desc_Screw_C\"',Amount=12))");
+        assert_eq!(amounts, vec![("Desc_IronPlate_C".to_string(), 6.0), ("Desc_Screw_C".to_string(), 12.0)]);
+        assert!(item_amounts("").is_empty());
+    }
+
+    #[test]
+    fn schematic_unlocks() {
+        let schematic = |class: &str, kind: &str, tier: &str| serde_json::json!({ "ClassName": class, "mType": kind, "mTechTier": tier });
+        assert_eq!(schematic_unlock(&schematic("Schematic_3-2_C", "EST_Milestone", "3")), Some(Unlock { tier: 3, research: None }));
+        assert_eq!(schematic_unlock(&schematic("Research_Quartz_2_C", "EST_MAM", "0")), Some(Unlock { tier: 0, research: Some("Quartz".to_string()) }));
+        // Not a tree we track
+        assert_eq!(schematic_unlock(&schematic("Research_Mycelia_1_C", "EST_MAM", "0")), None);
+        assert_eq!(schematic_unlock(&schematic("Schematic_Alternate_Screw_C", "EST_Alternate", "0")), None);
+    }
+}
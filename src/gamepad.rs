@@ -0,0 +1,87 @@
+// Gamepad buttons: through the browser's Gamepad API on the web build (the JS side lives in web.js),
+// with gilrs on native builds as macroquad has no gamepad support yet.
+
+// Index in the Gamepad API standard mapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    A = 0,
+    DpadUp = 12,
+    DpadDown = 13,
+    DpadLeft = 14,
+    DpadRight = 15,
+}
+
+#[cfg(target_arch = "wasm32")]
+extern "C" {
+    // One bit per pressed button of every connected gamepad
+    fn gamepad_buttons() -> u32;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+pub extern "C" fn gamepad_crate_version() -> u32 {
+    1 << 16
+}
+
+// Button state of this frame and the last one, call update once per frame
+pub struct Gamepad {
+    previous: u32,
+    current: u32,
+    // None when no backend is available on this platform
+    #[cfg(not(target_arch = "wasm32"))]
+    gilrs: Option<gilrs::Gilrs>,
+}
+
+impl Default for Gamepad {
+    fn default() -> Self {
+        Gamepad {
+            previous: 0,
+            current: 0,
+            #[cfg(not(target_arch = "wasm32"))]
+            gilrs: gilrs::Gilrs::new().map_err(|e| macroquad::logging::warn!("No gamepad support: {}", e)).ok(),
+        }
+    }
+}
+
+impl Gamepad {
+    pub fn update(&mut self) {
+        self.previous = self.current;
+        self.current = self.buttons();
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn buttons(&mut self) -> u32 {
+        unsafe { gamepad_buttons() }
+    }
+
+    // Same bits as the web build, from every connected gamepad
+    #[cfg(not(target_arch = "wasm32"))]
+    fn buttons(&mut self) -> u32 {
+        let Some(gilrs) = &mut self.gilrs else { return 0 };
+        // Events have to be consumed for the state to update
+        while gilrs.next_event().is_some() {}
+
+        let mapping = [
+            (gilrs::Button::South, Button::A),
+            (gilrs::Button::DPadUp, Button::DpadUp),
+            (gilrs::Button::DPadDown, Button::DpadDown),
+            (gilrs::Button::DPadLeft, Button::DpadLeft),
+            (gilrs::Button::DPadRight, Button::DpadRight),
+        ];
+        let mut result = 0;
+        for (_, pad) in gilrs.gamepads() {
+            for (from, to) in mapping {
+                if pad.is_pressed(from) {
+                    result |= 1 << to as u32;
+                }
+            }
+        }
+        result
+    }
+
+    // Went down this frame
+    pub fn is_pressed(&self, button: Button) -> bool {
+        let bit = 1 << button as u32;
+        self.current & bit != 0 && self.previous & bit == 0
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use crate::items::*;
+
+pub struct RecipeGraph<'a> {
+    defaults: &'a DefaultRecipes,
+    alternates: &'a [Recipe],
+}
+
+impl<'a> RecipeGraph<'a> {
+    pub fn new(defaults: &'a DefaultRecipes, alternates: &'a [Recipe]) -> Self {
+        Self { defaults, alternates }
+    }
+
+    // Items without a default recipe (Heavy Oil Residue, Polymer Resin...) fall back to the first alternate making them
+    fn recipe_for(&self, item: &Item) -> Option<&'a Recipe> {
+        self.defaults.get(item.name()).or_else(|| self.alternates.iter().find(|r| r.product().name.id == item.id))
+    }
+
+    // Raw resources needed for one unit of the recipe's main product.
+    // The whole cost of a recipe goes to its main product, byproducts are not credited.
+    pub fn raw_cost(&self, recipe: &Recipe) -> Vec<Ingredients> {
+        let mut totals: Vec<Ingredients> = Vec::new();
+        let mut visiting = vec![recipe.product().name.id];
+        self.expand_recipe(recipe, 1.0, &mut visiting, &mut totals);
+
+        // Same order as the catalog, which lists raw resources first
+        totals.sort_by_key(|i| i.name.id);
+        totals
+    }
+
+    fn expand_recipe(&self, recipe: &Recipe, amount: f32, visiting: &mut Vec<ItemId>, totals: &mut Vec<Ingredients>) {
+        let product = recipe.product();
+        if product.nb <= 0.0 {
+            return;
+        }
+        let crafts = amount / product.nb;
+        for inp in &recipe.input {
+            self.expand(&inp.name, inp.nb * crafts, visiting, totals);
+        }
+    }
+
+    fn expand(&self, item: &Item, amount: f32, visiting: &mut Vec<ItemId>, totals: &mut Vec<Ingredients>) {
+        let recipe = if item.info().raw || visiting.contains(&item.id) { None } else { self.recipe_for(item) };
+        match recipe {
+            Some(recipe) => {
+                visiting.push(item.id);
+                self.expand_recipe(recipe, amount, visiting, totals);
+                visiting.pop();
+            },
+            // Raw resources, and items we can't craft (Uranium Waste) or that loop back on themselves
+            None => {
+                match totals.iter_mut().find(|t| t.name.id == item.id) {
+                    Some(total) => total.nb += amount,
+                    None => totals.push(Ingredients { name: item.id.into(), nb: amount }),
+                }
+            },
+        }
+    }
+}
+
+// Which alternates consume and produce each item
+#[derive(Debug, Default)]
+pub struct ItemUses {
+    consumers: HashMap<ItemId, Vec<String>>,
+    producers: HashMap<ItemId, Vec<String>>,
+}
+
+impl ItemUses {
+    pub fn new(alternates: &[Recipe]) -> Self {
+        let mut result = Self::default();
+        for recipe in alternates {
+            for inp in &recipe.input {
+                result.consumers.entry(inp.name.id).or_default().push(recipe.name.clone());
+            }
+            for out in &recipe.output {
+                result.producers.entry(out.name.id).or_default().push(recipe.name.clone());
+            }
+        }
+        result
+    }
+
+    pub fn consumers(&self, item: ItemId) -> &[String] {
+        self.consumers.get(&item).map_or(&[], |v| v.as_slice())
+    }
+
+    pub fn producers(&self, item: ItemId) -> &[String] {
+        self.producers.get(&item).map_or(&[], |v| v.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> (DefaultRecipes, Vec<Recipe>) {
+        (serde_json::from_str(include_str!("../res/default_recipes.json")).unwrap(),
+         serde_json::from_str(include_str!("../res/recipes.json")).unwrap())
+    }
+
+    fn cost(graph: &RecipeGraph, recipes: &[Recipe], name: &str) -> Vec<(String, f32)> {
+        let recipe = recipes.iter().find(|r| r.name == name).unwrap();
+        graph.raw_cost(recipe).iter().map(|i| (i.name.name().to_string(), i.nb)).collect()
+    }
+
+    fn assert_cost(cost: &[(String, f32)], expected: &[(&str, f32)]) {
+        assert_eq!(cost.len(), expected.len(), "{:?}", cost);
+        for (name, nb) in expected {
+            let found = cost.iter().find(|(n, _)| n == name).unwrap_or_else(|| panic!("no {} in {:?}", name, cost));
+            assert!((found.1 - nb).abs() < 1e-4, "{:?}", cost);
+        }
+    }
+
+    #[test]
+    fn through_default_recipes() {
+        let (defaults, alternates) = data();
+        let graph = RecipeGraph::new(&defaults, &alternates);
+        // 12.5 Iron Ingot for 50 screws, an ingot per ore
+        assert_cost(&cost(&graph, &alternates, "Cast Screw"), &[("Iron Ore", 0.25)]);
+    }
+
+    #[test]
+    fn water_is_raw_and_residue_from_an_alternate() {
+        let (defaults, alternates) = data();
+        let graph = RecipeGraph::new(&defaults, &alternates);
+        // 0.5 Heavy Oil Residue per fuel, from the Heavy Oil Residue alternate at 30 Crude Oil for 40
+        assert_cost(&cost(&graph, &alternates, "Diluted Fuel"), &[("Crude Oil", 0.375), ("Water", 1.0)]);
+    }
+
+    #[test]
+    fn cycles_stop() {
+        let defaults = DefaultRecipes::new();
+        let alternates = vec![
+            Recipe::test("Rod from Screw", &[("Screw", 10.0)], &[("Iron Rod", 10.0)]),
+            Recipe::test("Screw from Rod", &[("Iron Rod", 10.0)], &[("Screw", 40.0)]),
+        ];
+        let graph = RecipeGraph::new(&defaults, &alternates);
+        // Back to the rod being made, counted as is
+        assert_cost(&cost(&graph, &alternates, "Rod from Screw"), &[("Iron Rod", 0.25)]);
+    }
+}
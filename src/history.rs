@@ -0,0 +1,69 @@
+use serde::{Serialize, Deserialize};
+
+use crate::storage;
+
+const STORAGE_KEY: &str = "history";
+
+// One confirmed hard drive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pick {
+    // Seconds since the unix epoch
+    pub timestamp: f64,
+    pub seed: u64,
+    pub draw: u32,
+    pub offered: Vec<String>,
+    pub chosen: String,
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    pub picks: Vec<Pick>,
+}
+
+impl History {
+    pub fn load() -> Self {
+        let picks = storage::load(STORAGE_KEY).and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default();
+        Self { picks }
+    }
+
+    pub fn record(&mut self, pick: Pick) {
+        self.picks.push(pick);
+        match serde_json::to_string(&self.picks) {
+            Ok(s) => storage::save(STORAGE_KEY, &s),
+            Err(e) => macroquad::logging::error!("Unable to save the history: {}", e),
+        }
+    }
+}
+
+// "YYYY-MM-DD HH:MM" in UTC
+pub fn format_timestamp(timestamp: f64) -> String {
+    let secs = timestamp as i64;
+    let days = secs.div_euclid(86400);
+    let time = secs.rem_euclid(86400);
+
+    // Days to civil date, from Howard Hinnant's date algorithms
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, time / 3600, (time % 3600) / 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(format_timestamp(0.0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1700000000.0), "2023-11-14 22:13");
+        assert_eq!(format_timestamp(951825599.9), "2000-02-29 11:59");
+        assert_eq!(format_timestamp(-60.0), "1969-12-31 23:59");
+    }
+}
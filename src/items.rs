@@ -1,8 +1,10 @@
-use macroquad::prelude::*; 
+#[cfg(feature = "game")]
+use macroquad::prelude::*;
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 
-use crate::ItemTextureMap;
+#[cfg(feature = "game")]
+pub type ItemTextureMap = HashMap<ItemId, Texture2D>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Ingredients {
@@ -10,220 +12,299 @@ pub struct Ingredients {
     pub nb: f32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Building {
+    Smelter,
+    Foundry,
+    Constructor,
+    Assembler,
+    Manufacturer,
+    Refinery,
+    Blender,
+    Packager,
+    #[serde(rename = "Particle Accelerator")]
+    ParticleAccelerator,
+}
+
+impl Building {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Building::Smelter => "Smelter",
+            Building::Foundry => "Foundry",
+            Building::Constructor => "Constructor",
+            Building::Assembler => "Assembler",
+            Building::Manufacturer => "Manufacturer",
+            Building::Refinery => "Refinery",
+            Building::Blender => "Blender",
+            Building::Packager => "Packager",
+            Building::ParticleAccelerator => "Particle Accelerator",
+        }
+    }
+
+    // Base power usage in MW at 100% clock speed
+    pub fn power(&self) -> f32 {
+        match self {
+            Building::Smelter => 4.0,
+            Building::Foundry => 16.0,
+            Building::Constructor => 4.0,
+            Building::Assembler => 15.0,
+            Building::Manufacturer => 55.0,
+            Building::Refinery => 30.0,
+            Building::Blender => 75.0,
+            Building::Packager => 10.0,
+            // Varies during the craft between 250 and 750 MW, this is the average
+            Building::ParticleAccelerator => 500.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Recipe {
     pub name: String,
-    pub product: Item,
+    pub building: Building,
+    // Milestone tier from which the building and all the items are available
+    pub tier: u8,
+    // MAM researches needed for some of the items
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub research: Vec<String>,
+    // Seconds per craft, the amounts are per minute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f32>,
     pub input: Vec<Ingredients>,
-    pub rate: f32,
+    // The first output is the main product, the others are byproducts
+    pub output: Vec<Ingredients>,
 }
 
 impl Recipe {
+    pub fn product(&self) -> &Ingredients {
+        &self.output[0]
+    }
+
+    // Amount used or made by one craft, when the cycle time is known.
+    // Rates are rounded to 0.1/min so this is only close: solids go by whole items, fluids by 0.1 m³.
+    pub fn per_cycle(&self, ingredient: &Ingredients) -> Option<f32> {
+        let precision = if ingredient.name.info().is_fluid { 10.0 } else { 1.0 };
+        self.duration.map(|d| (ingredient.nb * d / 60.0 * precision).round() / precision)
+    }
+
+    pub fn output_rate(&self, item: ItemId) -> Option<f32> {
+        self.output.iter().find(|o| o.name.id == item).map(|o| o.nb)
+    }
+
+    // Lines up our inputs with the ones of `default`, scaled so that both recipes make as much of our product
+    pub fn compare(&self, default: &Recipe) -> Comparison {
+        let product = self.product();
+        let scale = match default.output_rate(product.name.id) {
+            Some(rate) if rate > 0.0 => product.nb / rate,
+            _ => 1.0,
+        };
+
+        let mut result = Comparison { default: Vec::new(), alternate: Vec::new() };
+        for inp in &default.input {
+            let alt = self.input.iter().find(|i| i.name.id == inp.name.id).map_or(0.0, |i| i.nb);
+            result.default.push(Ingredients { name: inp.name.clone(), nb: inp.nb * scale });
+            result.alternate.push(Ingredients { name: inp.name.clone(), nb: alt });
+        }
+        for inp in &self.input {
+            if default.input.iter().all(|i| i.name.id != inp.name.id) {
+                result.default.push(Ingredients { name: inp.name.clone(), nb: 0.0 });
+                result.alternate.push(inp.clone());
+            }
+        }
+        result
+    }
+
+    // Constructor recipe at tier 0, for the tests
+    #[cfg(test)]
+    pub fn test(name: &str, input: &[(&str, f32)], output: &[(&str, f32)]) -> Recipe {
+        let ingredients = |list: &[(&str, f32)]| list.iter().map(|(n, nb)| Ingredients { name: n.to_string().try_into().unwrap(), nb: *nb }).collect();
+        Recipe { name: name.to_string(), building: Building::Constructor, tier: 0, research: Vec::new(), duration: None, input: ingredients(input), output: ingredients(output) }
+    }
+
+    #[cfg(feature = "game")]
     pub async fn load(&mut self, texs: &mut ItemTextureMap) {
-        self.product.load(texs).await;
+        for out in &mut self.output {
+            out.name.load(texs).await;
+        }
         for inp in &mut self.input {
             inp.name.load(texs).await;
         }
     }
 }
 
+pub type DefaultRecipes = HashMap<String, Recipe>;
+
+pub const MAX_TIER: u8 = 8;
+pub const RESEARCHES: [&str; 3] = ["Caterium", "Quartz", "Sulfur"];
+
+// How far the player is in the game, a hard drive only offers alternates they could use
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unlocks {
+    pub tier: u8,
+    pub research: Vec<String>,
+}
+
+impl Default for Unlocks {
+    fn default() -> Self {
+        Self {
+            tier: MAX_TIER,
+            research: RESEARCHES.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+}
+
+impl Unlocks {
+    pub fn allows(&self, recipe: &Recipe) -> bool {
+        recipe.tier <= self.tier && recipe.research.iter().all(|r| self.research.contains(r))
+    }
+}
+
+// Both lists have the same items in the same order, missing inputs are 0
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub default: Vec<Ingredients>,
+    pub alternate: Vec<Ingredients>,
+}
+
+impl Comparison {
+    pub fn delta(&self, i: usize) -> f32 {
+        self.alternate[i].nb - self.default[i].nb
+    }
+}
+
+// Index in the catalog, only valid for the items.json it was loaded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ItemId(usize);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ItemInfo {
+    pub name: String,
+    // File in res/images
+    #[serde(default)]
+    pub icon: Option<String>,
+    pub category: String,
+    // Fluids don't stack
+    #[serde(default)]
+    pub stack_size: Option<u32>,
+    #[serde(default)]
+    pub is_fluid: bool,
+    // Mined, extracted or gathered and never crafted
+    #[serde(default)]
+    pub raw: bool,
+}
+
+// Every item the recipes can use, from res/items.json
+#[derive(Debug, Default)]
+pub struct ItemCatalog {
+    items: Vec<ItemInfo>,
+    by_name: HashMap<String, ItemId>,
+}
+
+impl ItemCatalog {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let items: Vec<ItemInfo> = serde_json::from_str(json).map_err(|e| format!("Unable to parse the item catalog: {}", e))?;
+        let mut by_name = HashMap::new();
+        for (i, item) in items.iter().enumerate() {
+            if by_name.insert(item.name.clone(), ItemId(i)).is_some() {
+                return Err(format!("{} is in the item catalog twice", item.name));
+            }
+        }
+        Ok(Self { items, by_name })
+    }
+
+    pub fn get(&self, id: ItemId) -> &ItemInfo {
+        &self.items[id.0]
+    }
+
+    pub fn find(&self, name: &str) -> Option<ItemId> {
+        self.by_name.get(name).copied()
+    }
+}
+
+lazy_static! {
+pub static ref ITEMS: ItemCatalog = ItemCatalog::from_json(include_str!("../res/items.json")).unwrap();
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(from = "String")]
+#[serde(try_from = "String", into = "String")]
 pub struct Item {
-    pub name: String,
-    #[serde(skip)]
+    pub id: ItemId,
+    // Only the game draws them, the tools don't link macroquad
+    #[cfg(feature = "game")]
     pub texture: Option<Texture2D>,
 }
 
-impl From<String> for Item {
-    fn from(value: String) -> Self {
+impl TryFrom<String> for Item {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match ITEMS.find(&value) {
+            Some(id) => Ok(Self::from(id)),
+            None => Err(format!("Unknown item {}, add it to res/items.json", value)),
+        }
+    }
+}
+
+impl From<ItemId> for Item {
+    fn from(id: ItemId) -> Self {
         Self {
-            name: value,
-            texture: None
+            id,
+            #[cfg(feature = "game")]
+            texture: None,
         }
     }
 }
 
+impl From<Item> for String {
+    fn from(value: Item) -> Self {
+        value.name().to_string()
+    }
+}
+
 impl Item {
+    pub fn info(&self) -> &'static ItemInfo {
+        ITEMS.get(self.id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        &self.info().name
+    }
+
+    #[cfg(feature = "game")]
     pub async fn load(&mut self, texs: &mut ItemTextureMap) {
-        if let Some(_) = self.texture {
+        if self.texture.is_some() {
             return;
         }
+        if let Some(t) = texs.get(&self.id) {
+            self.texture = Some(*t);
+            return;
+        }
+        let Some(icon) = &self.info().icon else {
+            return;
+        };
 
-        self.texture = if let Some(v) = texs.get_mut(&self.name as &str) {
-            if let Some(t) = *v { 
-                Some(t)
-            } else {
-                let path = format!("res/images/{}", IMAGE_MAP.get(&self.name as &str).unwrap());
-                match load_texture(&path).await {
-                    Ok(tex) => {
-                        *v = Some(tex);
-                        Some(tex)
-                    },
-                    Err(e) => {
-                        error!("Unable to load {}: {}", path, e);
-                        None
-                    },
-                }
-            }
-        } else { 
-            None
+        let path = format!("res/images/{}", icon);
+        match load_texture(&path).await {
+            Ok(tex) => {
+                texs.insert(self.id, tex);
+                self.texture = Some(tex);
+            },
+            Err(e) => error!("Unable to load {}: {}", path, e),
         }
     }
 }
 
-lazy_static! {
-pub static ref IMAGE_MAP: HashMap<&'static str, &'static str> = HashMap::from([
-    ("Iron Ore", "Iron_Ore.png"),
-    ("Copper Ore", "Copper_Ore.png"),
-    ("Iron Plate", "Iron_Plate.png"),
-    ("Screw", "Screw.png"),
-    ("Wire", "Wire.png"),
-    ("Iron Ingot", "Iron_Ingot.png"),
-    ("Sulfur", "Sulfur.png"),
-    ("Compacted Coal", "Compacted_Coal.png"),
-    ("Caterium Ingot", "Caterium_Ingot.png"),
-    ("Copper Ingot", "Copper_Ingot.png"),
-    ("Silica", "Silica.png"),
-    ("Limestone", "Limestone.png"),
-    ("Raw Quartz", "Raw_Quartz.png"),
-    ("Reinforced Iron Plate", "Reinforced_Iron_Plate.png"),
-    ("Copper Sheet", "Copper_Sheet.png"),
-    ("Steel Ingot", "Steel_Ingot.png"),
-    ("Steel Pipe", "Steel_Pipe.png"),
-    ("Steel Beam", "Steel_Beam.png"),
-    ("Coal", "Coal.png"),
-    ("Crystal Oscillator", "Crystal_Oscillator.png"),
-    ("Biomass", "Biomass.png"),
-    ("Wood", "Wood.png"),
-    ("Water", "Water.png"),
-    ("Caterium Ore", "Caterium_Ore.png"),
-    ("Concrete", "Concrete.png"),
-    ("Stator", "Stator.png"),
-    ("High-Speed Connector", "High-Speed_Connector.png"),
-    ("Quickwire", "Quickwire.png"),
-    ("Rotor", "Rotor.png"),
-    ("Modular Frame", "Modular_Frame.png"),
-    ("Encased Industrial Beam", "Encased_Industrial_Beam.png"),
-    ("Rubber", "Rubber.png"),
-    ("Motor", "Motor.png"),
-    ("Iron Rod", "Iron_Rod.png"),
-    ("Circuit Board", "Circuit_Board.png"),
-    ("Heavy Oil Residue", "Heavy_Oil_Residue.png"),
-    ("Petroleum Coke", "Petroleum_Coke.png"),
-    ("Crude Oil", "Crude_Oil.png"),
-    ("Plastic", "Plastic.png"),
-    ("Packaged Water", "Packaged_Water.png"),
-    ("Fuel", "Fuel.png"),
-    ("Quartz Crystal", "Quartz_Crystal.png"),
-    ("AI Limiter", "AI_Limiter.png"),
-    ("Alclad Aluminum Sheet", "Alclad_Aluminum_Sheet.png"),
-    ("Electromagnetic Control Rod", "Electromagnetic_Control_Rod.png"),
-    ("Radio Control Unit", "Radio_Control_Unit.png"),
-    ("Cooling System", "Cooling_System.png"),
-    ("Computer", "Computer.png"),
-    ("Battery", "Battery.png"),
-    ("Bauxite", "Bauxite.png"),
-    ("Aluminum Ingot", "Aluminum_Ingot.png"),
-    ("Aluminum Scrap", "Aluminum_Scrap.png"),
-    ("Alumina Solution", "Alumina_Solution.png"),
-    ("Aluminum Casing", "Aluminum_Casing.png"),
-    ("Sulfuric Acid", "Sulfuric_Acid.png"),
-    ("Heat Sink", "Heat_Sink.png"),
-    ("Nitrogen Gas", "Nitrogen_Gas.png"),
-    ("Uranium", "Uranium.png"),
-    ("Encased Uranium Cell", "Encased_Uranium_Cell.png"),
-    ("Beacon", "Beacon.png"),
-    ("Non-fissile Uranium", "Non-fissile_Uranium.png"),
-    ("Heavy Modular Frame", "Heavy_Modular_Frame.png"),
-    ("Nitric Acid", "Nitric_Acid.png"),
-    ("Uranium Waste", "Uranium_Waste.png"),
-    ("Encased Plutonium Cell", "Encased_Plutonium_Cell.png"),
-    ("Pressure Conversion Cube", "Pressure_Conversion_Cube.png"),
-    ("Packaged Nitrogen Gas", "Packaged_Nitrogen_Gas.png"),
-    ("Iron Ingot", "Iron_Ingot.png"),
-    ("Reinforced Iron Plate", "Reinforced_Iron_Plate.png"),
-    ("Reinforced Iron Plate", "Reinforced_Iron_Plate.png"),
-    ("Screw", "Screw.png"),
-    ("Wire", "Wire.png"),
-    ("Black Powder", "Black_Powder.png"),
-    ("Wire", "Wire.png"),
-    ("Wire", "Wire.png"),
-    ("Quickwire", "Quickwire.png"),
-    ("Concrete", "Concrete.png"),
-    ("Silica", "Silica.png"),
-    ("Modular Frame", "Modular_Frame.png"),
-    ("Rotor", "Rotor.png"),
-    ("Iron Rod", "Iron_Rod.png"),
-    ("Modular Frame", "Modular_Frame.png"),
-    ("Rotor", "Rotor.png"),
-    ("Screw", "Screw.png"),
-    ("Steel Ingot", "Steel_Ingot.png"),
-    ("Steel Ingot", "Steel_Ingot.png"),
-    ("Beacon", "Beacon.png"),
-    ("Coal", "Coal.png"),
-    ("Coal", "Coal.png"),
-    ("Concrete", "Concrete.png"),
-    ("Copper Ingot", "Copper_Ingot.png"),
-    ("Copper Sheet", "Copper_Sheet.png"),
-    ("Iron Ingot", "Iron_Ingot.png"),
-    ("Caterium Ingot", "Caterium_Ingot.png"),
-    ("Quartz Crystal", "Quartz_Crystal.png"),
-    ("Encased Industrial Beam", "Encased_Industrial_Beam.png"),
-    ("Automated Wiring", "Automated_Wiring.png"),
-    ("Stator", "Stator.png"),
-    ("Motor", "Motor.png"),
-    ("Empty Canister", "Empty_Canister.png"),
-    ("Empty Canister", "Empty_Canister.png"),
-    ("Heavy Modular Frame", "Heavy_Modular_Frame.png"),
-    ("Heavy Modular Frame", "Heavy_Modular_Frame.png"),
-    ("Portable Miner", "Portable_Miner.png"),
-    ("Computer", "Computer.png"),
-    ("Computer", "Computer.png"),
-    ("Cable", "Cable.png"),
-    ("Cable", "Cable.png"),
-    ("Circuit Board", "Circuit_Board.png"),
-    ("Concrete", "Concrete.png"),
-    ("Heavy Oil Residue", "Heavy_Oil_Residue.png"),
-    ("Iron Plate", "Iron_Plate.png"),
-    ("Iron Plate", "Iron_Plate.png"),
-    ("Packaged Fuel", "Packaged_Fuel.png"),
-    ("Plastic", "Plastic.png"),
-    ("Polymer Resin", "Polymer_Resin.png"),
-    ("Reinforced Iron Plate", "Reinforced_Iron_Plate.png"),
-    ("Rubber", "Rubber.png"),
-    ("Smart Plating", "Smart_Plating.png"),
-    ("Steel Ingot", "Steel_Ingot.png"),
-    ("Versatile Framework", "Versatile_Framework.png"),
-    ("Cable", "Cable.png"),
-    ("Circuit Board", "Circuit_Board.png"),
-    ("High-Speed Connector", "High-Speed_Connector.png"),
-    ("Crystal Oscillator", "Crystal_Oscillator.png"),
-    ("Circuit Board", "Circuit_Board.png"),
-    ("Turbofuel", "Turbofuel.png"),
-    ("Battery", "Battery.png"),
-    ("Motor", "Motor.png"),
-    ("Supercomputer", "Supercomputer.png"),
-    ("Supercomputer", "Supercomputer.png"),
-    ("Alumina Solution", "Alumina_Solution.png"),
-    ("Aluminum Casing", "Aluminum_Casing.png"),
-    ("Aluminum Ingot", "Aluminum_Ingot.png"),
-    ("Aluminum Scrap", "Aluminum_Scrap.png"),
-    ("Fuel", "Fuel.png"),
-    ("Radio Control Unit", "Radio_Control_Unit.png"),
-    ("Aluminum Scrap", "Aluminum_Scrap.png"),
-    ("Turbofuel", "Turbofuel.png"),
-    ("Cooling System", "Cooling_System.png"),
-    ("Heat Sink", "Heat_Sink.png"),
-    ("Radio Control Unit", "Radio_Control_Unit.png"),
-    ("Turbo Motor", "Turbo_Motor.png"),
-    ("Electromagnetic Control Rod", "Electromagnetic_Control_Rod.png"),
-    ("Encased Uranium Cell", "Encased_Uranium_Cell.png"),
-    ("Uranium Fuel Rod", "Uranium_Fuel_Rod.png"),
-    ("Encased Plutonium Cell", "Encased_Plutonium_Cell.png"),
-    ("Fused Modular Frame", "Fused_Modular_Frame.png"),
-    ("Non-fissile Uranium", "Non-fissile_Uranium.png"),
-    ("Plutonium Fuel Rod", "Plutonium_Fuel_Rod.png"),
-    ("Turbo Motor", "Turbo_Motor.png"),
-]);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_cycle_amounts() {
+        let recipes: Vec<Recipe> = serde_json::from_str(include_str!("../res/recipes.json")).unwrap();
+        let recipe = |name: &str| recipes.iter().find(|r| r.name == name).unwrap();
+        let per_cycle = |recipe: &Recipe| -> Vec<f32> { recipe.input.iter().chain(&recipe.output).map(|i| recipe.per_cycle(i).unwrap()).collect() };
+
+        assert_eq!(per_cycle(recipe("Radio Connection Unit")), vec![4.0, 2.0, 12.0, 1.0]);
+        assert_eq!(per_cycle(recipe("Turbo Electric Motor")), vec![7.0, 9.0, 5.0, 7.0, 3.0]);
+    }
 }
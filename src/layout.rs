@@ -0,0 +1,47 @@
+// Sizes that follow the window. The UI is designed for 1280x720 (720x1280 in portrait) and scaled from there,
+// everything is computed from the current screen size so resizing just works.
+
+use macroquad::prelude::*;
+
+const DESIGN_LONG: f32 = 1280.0;
+const DESIGN_SHORT: f32 = 720.0;
+// Smaller is unreadable
+const MIN_FONT_SIZE: f32 = 10.0;
+
+// Taller than wide screens (phones) stack the cards instead of putting them side by side
+pub fn is_portrait() -> bool {
+    screen_height() > screen_width()
+}
+
+pub fn scale() -> f32 {
+    let (w, h) = (screen_width(), screen_height());
+    let (long, short) = if is_portrait() { (h, w) } else { (w, h) };
+    (long / DESIGN_LONG).min(short / DESIGN_SHORT).clamp(0.4, 4.0)
+}
+
+// Design pixels to screen pixels
+pub fn px(design: f32) -> f32 {
+    design * scale()
+}
+
+pub fn font_size(design: f32) -> u16 {
+    px(design).max(MIN_FONT_SIZE) as u16
+}
+
+// Two rows in portrait, the title, filters and tabs don't fit on one
+pub fn top_bar() -> f32 {
+    if is_portrait() { px(120.0) } else { px(75.0) }
+}
+
+pub fn bottom_bar() -> f32 {
+    px(75.0)
+}
+
+// Vertical center of a row of the top bar, landscape only has row 0
+pub fn top_bar_row(row: usize) -> f32 {
+    if is_portrait() {
+        top_bar() * (0.25 + 0.5 * row as f32)
+    } else {
+        top_bar() / 2.0
+    }
+}
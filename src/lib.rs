@@ -0,0 +1,10 @@
+// Recipe data and the tools working on it, shared by the game and the scraper
+
+#[macro_use]
+extern crate lazy_static;
+
+pub mod items;
+pub mod graph;
+pub mod docs;
+pub mod diff;
+pub mod validate;
@@ -1,16 +1,34 @@
-#[macro_use]
-extern crate lazy_static;
-
 use std::collections::HashMap;
 
-use macroquad::{prelude::{*, coroutines::*}, input};
+use macroquad::{prelude::{*, coroutines::*}, input, rand::RandGenerator};
+
+use satisfactory_alt_recipe::{items::*, graph::*, docs, diff, validate};
+
+mod web;
+mod storage;
+
+mod history;
+use history::*;
+
+mod stats;
+
+mod rating;
+use rating::*;
 
-// mod scrape;
-// #[allow(unused_imports)]
-// use scrape::*;
+mod gamepad;
+use gamepad::{Gamepad, Button};
 
-mod items;
-use items::*;
+mod touch;
+use touch::*;
+
+mod layout;
+use layout::{is_portrait, px, top_bar, bottom_bar, top_bar_row};
+
+mod anim;
+use anim::CardAnimation;
+
+mod screens;
+use screens::*;
 
 const DARK_GRAY: Color = color_u8!(0x3F, 0x3F, 0x3F, 0xFF);
 const GRAY: Color = color_u8!(0x65, 0x65, 0x65, 0xFF);
@@ -18,35 +36,73 @@ const LIGHT_GRAY: Color = color_u8!(0x90, 0x90, 0x90, 0xFF);
 const BLACK: Color = color_u8!(0x0d,0x0d,0x0d,0xff); 
 const WHITE: Color = color_u8!(0xff,0xff,0xff,0xff); 
 const ORANGE: Color = color_u8!(0xe4,0x93,0x43,0xff); 
-
-const BORDER_SIZE: f32 = 75.0;
+const GREEN: Color = color_u8!(0x7c,0xc5,0x5e,0xff); 
+const RED: Color = color_u8!(0xe0,0x55,0x4b,0xff); 
+const FLUID_BLUE: Color = color_u8!(0x4f,0x9d,0xd8,0xff);
+// Keyboard / gamepad focus, must stand out from the ORANGE selection
+const FOCUS: Color = color_u8!(0x5f,0xd4,0xe8,0xff);
 
 struct Resources {
     warning_icon: Texture2D, 
-    recipes: Vec<Recipe>,
+    library: Library,
     font: Font,
-    item_textures: ItemTextureMap,
     globe: Texture2D,
     mam: Texture2D,
     checkmark: Texture2D,
     font_med: u16,
     font_big: u16,
+    // Product of every alternate, for the screens that list them all.
+    // Uses its own textures as the library's ones are often in the loading coroutine.
+    recipe_icons: HashMap<String, Item>,
+    ui_textures: ItemTextureMap,
+    item_uses: ItemUses,
 }
 
-type ItemTextureMap = HashMap<&'static str, Option<Texture2D>>;
+// Everything select_recipes needs, moved in and out of the loading coroutine
+struct Library {
+    recipes: Vec<Recipe>,
+    defaults: DefaultRecipes,
+    item_textures: ItemTextureMap,
+    // Separate from the global generator so that a seed always gives the same draws
+    rng: RandGenerator,
+    // Names of the alternates unlocked during a campaign, they aren't offered anymore
+    excluded: Vec<String>,
+    unlocks: Unlocks,
+}
+
+// A recipe offered by the hard drive, along with the default recipe it replaces
+struct Offer {
+    recipe: Recipe,
+    default: Option<Recipe>,
+    // Per unit of product
+    raw_cost: Vec<Ingredients>,
+}
 
 impl Resources {
-    pub async fn new() -> Resources {
+    // The recipes are checked beforehand, everything here expects each one to have a product
+    pub async fn new(recipes: Vec<Recipe>, defaults: DefaultRecipes) -> Resources {
+        let library = Library {
+            recipes,
+            defaults,
+            item_textures: ItemTextureMap::new(),
+            rng: RandGenerator::new(),
+            excluded: Vec::new(),
+            unlocks: Unlocks::default(),
+        };
+        let recipe_icons = library.recipes.iter().map(|r| (r.name.clone(), r.product().name.clone())).collect();
+        let item_uses = ItemUses::new(&library.recipes);
         Resources {
             warning_icon: Texture2D::from_file_with_format(include_bytes!("../res/warning.png"), None),
-            recipes: serde_json::from_str(include_str!("../res/recipes.json")).unwrap(),
+            library,
             font : load_ttf_font("res/DejaVuSans.ttf").await.unwrap(),
-            item_textures: init_images(),
             globe: Texture2D::from_file_with_format(include_bytes!("../res/globe.png"), None),
             mam: Texture2D::from_file_with_format(include_bytes!("../res/mam.png"), None),
             checkmark: Texture2D::from_file_with_format(include_bytes!("../res/ficsit_check.png"), None),
-            font_med : ((screen_height() / 720.0) * 15.0) as u16,
-            font_big : ((screen_height() / 720.0) * 20.0) as u16,
+            font_med : layout::font_size(15.0),
+            font_big : layout::font_size(20.0),
+            recipe_icons,
+            ui_textures: ItemTextureMap::new(),
+            item_uses,
         }
     }
 }
@@ -54,12 +110,11 @@ impl Resources {
 // --------
 // Loading
 
-fn init_images() -> ItemTextureMap {
-    let mut result: ItemTextureMap = HashMap::new();
-    for i in items::IMAGE_MAP.iter() {
-        result.insert(i.0, None);
+// Takes the fields rather than Resources, the library is often moved out of it
+async fn load_recipe_icons(icons: &mut HashMap<String, Item>, texs: &mut ItemTextureMap) {
+    for icon in icons.values_mut() {
+        icon.load(texs).await;
     }
-    result
 }
 
 // --------
@@ -70,7 +125,7 @@ fn draw_centered_text(text: &str, x: f32, y: f32, text_params: TextParams) -> (f
     let x = x - measure.width / 2.0;
     let y = y + (measure.height / 2.0) - (measure.height - measure.offset_y);
     draw_text_ex(text, x, y, text_params);
-    return (x, y - measure.height, measure.width, measure.height);
+    (x, y - measure.height, measure.width, measure.height)
 }
 
 fn draw_aligned_text(text: &str, x: f32, y: f32, text_params: TextParams) -> (f32, f32) {
@@ -80,6 +135,82 @@ fn draw_aligned_text(text: &str, x: f32, y: f32, text_params: TextParams) -> (f3
     (x + measure.width, y + measure.height)
 }
 
+// Splits the text by word into lines no wider than max_width.
+// Past max_lines, or when a single word is too wide, the text is cut with an ellipsis.
+fn wrap_text(text: &str, max_width: f32, max_lines: usize, text_params: TextParams) -> Vec<String> {
+    let width = |t: &str| measure_text(t, Some(text_params.font), text_params.font_size, 1.0).width;
+
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        // Out of lines, the rest goes on the last one and gets cut
+        let full = lines.len() >= max_lines.max(1);
+        match lines.last_mut() {
+            Some(last) if full || width(&format!("{} {}", last, word)) <= max_width => {
+                last.push(' ');
+                last.push_str(word);
+            },
+            _ => lines.push(word.to_string()),
+        }
+    }
+
+    for line in lines.iter_mut() {
+        if width(line) > max_width {
+            while !line.is_empty() && width(&format!("{}…", line.trim_end())) > max_width {
+                line.pop();
+            }
+            *line = format!("{}…", line.trim_end());
+        }
+    }
+    lines
+}
+
+// Wrapped text with the first line centered vertically on y, returns the y of the last line
+fn draw_wrapped_text(text: &str, x: f32, y: f32, max_width: f32, max_lines: usize, text_params: TextParams) -> f32 {
+    let line_h = text_params.font_size as f32 * 1.2;
+    let mut y = y;
+    for (i, line) in wrap_text(text, max_width, max_lines, text_params).iter().enumerate() {
+        if i > 0 {
+            y += line_h;
+        }
+        draw_aligned_text(line, x, y, text_params);
+    }
+    y
+}
+
+// Largest size up to the one asked for at which the text fits in max_width
+fn fit_font_size(text: &str, max_width: f32, text_params: TextParams) -> u16 {
+    let width = measure_text(text, Some(text_params.font), text_params.font_size, 1.0).width;
+    if width <= max_width {
+        return text_params.font_size;
+    }
+    ((text_params.font_size as f32 * max_width / width).floor() as u16).max(1)
+}
+
+// Title of a screen, under the top bar
+fn draw_header(text: &str, font_size: u16, font: Font) {
+    let font_size = fit_font_size(text, screen_width() - px(20.0), TextParams { font, font_size, ..Default::default()});
+    draw_centered_text(text, screen_width() / 2.0, top_bar() + px(25.0), TextParams { font, font_size, color: WHITE, ..Default::default()});
+}
+
+fn format_amount(amount: f32) -> String {
+    if amount >= 100.0 {
+        format!("{:.0}", amount)
+    } else if amount >= 10.0 {
+        format!("{:.1}", amount)
+    } else {
+        format!("{:.2}", amount)
+    }
+}
+
+// Fluids are measured in m³
+fn format_rate(item: &Item, per_minute: f32) -> String {
+    if item.info().is_fluid {
+        format!("{} m³/min", per_minute)
+    } else {
+        format!("{} items/min", per_minute)
+    }
+}
+
 fn draw_centered_texture(texture: Texture2D, x: f32, y: f32, size: f32, color: Color) {
     let x = x - size / 2.0;
     let y = y - size / 2.0;
@@ -103,6 +234,14 @@ fn draw_rounded_rectangle(x: f32, y: f32, w: f32, h: f32, border: f32, color: Co
     draw_circle(x + w - border, y + h - border, border, color); // Lower Left
 }
 
+#[derive(PartialEq, Eq)]
+enum Screen {
+    Pick,
+    History,
+    Stats,
+    Leaderboard,
+}
+
 enum Alignement {
     Left,
     Center,
@@ -110,7 +249,7 @@ enum Alignement {
 
 fn draw_icon_text(text: &str, icon: Texture2D, x: f32, y: f32, alignement: Alignement, text_params: TextParams) {
     let icon_size: f32 = text_params.font_size as f32;
-    let pad = 5.0;
+    let pad = px(5.0);
     let mut size = measure_text(text, Some(text_params.font), text_params.font_size, 1.0);
     size.width += icon_size + pad; // padding
 
@@ -125,21 +264,213 @@ fn draw_icon_text(text: &str, icon: Texture2D, x: f32, y: f32, alignement: Align
 
 }
 
+// Set while drawing a frame when the mouse is over something, drawn last so it stays on top
+struct Tooltip {
+    title: String,
+    lines: Vec<String>,
+}
+
+fn item_tooltip(item: &Item, per_minute: f32, recipe: &str, uses: &ItemUses) -> Tooltip {
+    // Only a few names, the tooltip must stay readable
+    let list = |names: &[String]| -> Option<String> {
+        let others: Vec<&str> = names.iter().map(|n| n.as_str()).filter(|n| *n != recipe).collect();
+        match others.len() {
+            0 => None,
+            1..=4 => Some(others.join(", ")),
+            n => Some(format!("{} and {} more", others[..3].join(", "), n - 3)),
+        }
+    };
+    let mut lines = vec![format_rate(item, per_minute)];
+    if let Some(consumers) = list(uses.consumers(item.id)) {
+        lines.push(format!("Used by: {}", consumers));
+    }
+    if let Some(producers) = list(uses.producers(item.id)) {
+        lines.push(format!("Made by: {}", producers));
+    }
+    Tooltip { title: item.name().to_string(), lines }
+}
+
+fn draw_tooltip(tooltip: &Tooltip, font_size: u16, font: Font) {
+    let pad = px(8.0);
+    let line_h = font_size as f32 * 1.4;
+    let width = std::iter::once(&tooltip.title).chain(&tooltip.lines)
+        .map(|l| measure_text(l, Some(font), font_size, 1.0).width)
+        .fold(0.0, f32::max) + pad * 2.0;
+    let height = line_h * (tooltip.lines.len() + 1) as f32 + pad * 2.0;
+
+    // Below right of the cursor, kept on screen
+    let (mouse_x, mouse_y) = input::mouse_position();
+    let x = (mouse_x + px(16.0)).min(screen_width() - width).max(0.0);
+    let y = (mouse_y + px(16.0)).min(screen_height() - height).max(0.0);
+    draw_rectangle(x, y, width, height, Color { a: 0.95, ..BLACK });
+    draw_rectangle_lines(x, y, width, height, 1.0, LIGHT_GRAY);
+
+    let mut layout_y = y + pad + line_h / 2.0;
+    draw_aligned_text(&tooltip.title, x + pad, layout_y, TextParams { font, font_size, color: ORANGE, ..Default::default()});
+    for line in &tooltip.lines {
+        layout_y += line_h;
+        draw_aligned_text(line, x + pad, layout_y, TextParams { font, font_size, color: WHITE, ..Default::default()});
+    }
+}
+
 // --------
 // Ui elements
 
-fn draw_ingredient(item: &Item, x: &mut f32, y: f32, size: f32) {
+// Returns true when the mouse is over the tile
+fn draw_ingredient(item: &Item, x: &mut f32, y: f32, size: f32) -> bool {
     if let Some(tex) = item.texture {
-        draw_rounded_rectangle(*x, y, size, size, 5.0, LIGHT_GRAY);
+        let hovered = Rect::new(*x, y, size, size).contains(input::mouse_position().into());
+        if item.info().is_fluid {
+            // Round tile like the pipes' fluid icons
+            let radius = size / 2.0;
+            draw_circle(*x + radius, y + radius, radius, LIGHT_GRAY);
+            draw_circle_lines(*x + radius, y + radius, radius - px(1.5), px(3.0), FLUID_BLUE);
+        } else {
+            draw_rounded_rectangle(*x, y, size, size, px(5.0), LIGHT_GRAY);
+        }
         draw_centered_texture(tex, *x + size / 2.0, y + size / 2.0, size * 0.90, WHITE);
-        *x += size + 5.0;
+        *x += size + px(5.0);
+        hovered
+    } else {
+        false
+    }
+}
+
+// There are no building images in res/images, their initials stand in for them
+fn draw_building(building: Building, x: f32, y: f32, size: f32, font: Font) {
+    draw_rounded_rectangle(x, y - size / 2.0, size, size, px(5.0), GRAY);
+    let initials: String = building.name().split(' ').filter_map(|w| w.chars().next()).collect();
+    draw_centered_text(&initials, x + size / 2.0, y, TextParams { font, font_size: (size * 0.6) as u16, color: WHITE, ..Default::default()});
+}
+
+fn draw_comparison(recipe: &Recipe, default: &Recipe, rect: Rect, font_size: u16, font: Font) {
+    draw_rounded_rectangle(rect.x, rect.y, rect.w, rect.h, px(10.0), DARK_GRAY);
+
+    let pad = px(10.0);
+    let mut layout_y = rect.y + pad + font_size as f32 / 2.0;
+    draw_wrapped_text(&format!("Replaces: {}", default.name), rect.x + pad, layout_y, rect.w - pad * 2.0, 1, TextParams { font_size, font, ..Default::default()});
+    layout_y += font_size as f32 * 2.0;
+
+    let columns = [rect.x + rect.w * 0.4, rect.x + rect.w * 0.6, rect.x + rect.w * 0.8];
+    for (x, title) in columns.iter().zip(["Default", "Alternate", "Δ / min"]) {
+        draw_centered_text(title, *x, layout_y, TextParams { font_size, font, color: LIGHT_GRAY, ..Default::default()});
+    }
+    layout_y += font_size as f32 * 1.5;
+
+    let comparison = recipe.compare(default);
+    // Shrink the rows when there are too many inputs to fit
+    let available = rect.y + rect.h - pad - layout_y;
+    let icon_size = (font_size as f32 * 1.5).min(available / comparison.alternate.len().max(1) as f32 - px(5.0));
+    for i in 0..comparison.alternate.len() {
+        let mut layout_x = rect.x + pad;
+        draw_ingredient(&comparison.alternate[i].name, &mut layout_x, layout_y - icon_size / 2.0, icon_size);
+
+        let delta = comparison.delta(i);
+        let delta_color = if delta > 0.0 { RED } else if delta < 0.0 { GREEN } else { WHITE };
+        draw_centered_text(&format!("{:.1}", comparison.default[i].nb), columns[0], layout_y, TextParams { font_size, font, ..Default::default()});
+        draw_centered_text(&format!("{:.1}", comparison.alternate[i].nb), columns[1], layout_y, TextParams { font_size, font, ..Default::default()});
+        draw_centered_text(&format!("{:+.1}", delta), columns[2], layout_y, TextParams { font_size, font, color: delta_color, ..Default::default()});
+        layout_y += icon_size + px(5.0);
+    }
+}
+
+// Number under an ingredient tile, per craft when asked and known
+fn tile_amount(recipe: &Recipe, ingredient: &Ingredients, per_cycle: bool) -> String {
+    let amount = if per_cycle { recipe.per_cycle(ingredient) } else { None };
+    format_amount(amount.unwrap_or(ingredient.nb))
+}
+
+// Cards are centered when there are fewer than three
+fn card_rect(i: usize, count: usize) -> Rect {
+    let top = top_bar() + px(50.0);
+    let available_h = screen_height() - bottom_bar() - px(50.0) - top;
+    if is_portrait() {
+        let h = available_h / 3.0;
+        let y = top + (available_h - h * count as f32) / 2.0 + h * i as f32;
+        Rect::new(px(10.0), y, screen_width() - px(20.0), h)
+    } else {
+        let w = screen_width() / 3.0;
+        let x = (screen_width() - w * count as f32) / 2.0 + w * i as f32;
+        Rect::new(x, top, w, available_h)
     }
+}
+
+// Outline of the element the keyboard or gamepad is on
+fn draw_focus(rect: Rect) {
+    let thickness = px(4.0);
+    draw_rectangle_lines(rect.x - thickness, rect.y - thickness, rect.w + thickness * 2.0, rect.h + thickness * 2.0, thickness, FOCUS);
+}
+
+// Shown in place of the cards while the hard drive is being analyzed
+fn draw_scan(progress: f32, globe: Texture2D, font_size: u16, font: Font) {
+    let (x, y) = (screen_width() / 2.0, screen_height() / 2.0);
+    let size = px(200.0);
+    draw_texture_ex(globe, x - size / 2.0, y - size / 2.0, Color::from_rgba(0xff, 0xff, 0xff, 0x40), DrawTextureParams {
+        dest_size: Some(Vec2::new(size, size)),
+        rotation: progress * std::f32::consts::TAU,
+        ..Default::default()
+    });
+    // Scan line going down the globe
+    let line_y = y - size / 2.0 + size * anim::ease_in_out_quad(progress);
+    draw_line(x - size / 2.0, line_y, x + size / 2.0, line_y, px(2.0), ORANGE);
 
+    let bar = Rect::new(x - px(200.0), y + size / 2.0 + px(30.0), px(400.0), px(8.0));
+    draw_rectangle(bar.x, bar.y, bar.w, bar.h, DARK_GRAY);
+    draw_rectangle(bar.x, bar.y, bar.w * progress, bar.h, ORANGE);
+    draw_centered_text(&format!("{:.0}%", progress * 100.0), x, bar.y + bar.h + px(20.0), TextParams { font, font_size, color: LIGHT_GRAY, ..Default::default()});
 }
 
-fn recipe_button(recipe: &Recipe, offset_x: f32, selected: bool, font_size: u16, font: Font, globe: Texture2D) -> bool {
-    // Calc extent 
-    let rect = Rect::new(offset_x, BORDER_SIZE + 50.0, screen_width() / 3.0, screen_height() - (BORDER_SIZE + 50.0) * 2.0);
+// Label then each item's icon (or name) and amount, wrapped under the label past max_x.
+// What doesn't fit in max_lines is counted in a "+N more". Returns the y of the last line.
+fn draw_amount_row(label: &str, entries: &[(&Item, String)], x: f32, y: f32, max_x: f32, max_lines: usize, params: TextParams) -> f32 {
+    let width = |t: &str| measure_text(t, Some(params.font), params.font_size, 1.0).width;
+    let icon_size = params.font_size as f32;
+    let gap = px(8.0);
+    let entry_width = |(item, amount): &(&Item, String)| {
+        let head = if item.texture.is_some() { icon_size + px(2.0) } else { width(&format!("{} ", item.name())) };
+        head + width(amount)
+    };
+
+    let (mut layout_x, _) = draw_aligned_text(label, x, y, params);
+    let mut layout_y = y;
+    let mut line = 1;
+    for (i, entry) in entries.iter().enumerate() {
+        let more = format!("+{} more", entries.len() - i);
+        // On the last line, keep room to say how many are left
+        let needed = entry_width(entry) + if line == max_lines && i + 1 < entries.len() { gap + width(&format!("+{} more", entries.len() - i - 1)) } else { 0.0 };
+        if layout_x + needed > max_x {
+            if line < max_lines {
+                line += 1;
+                layout_x = x;
+                layout_y += params.font_size as f32 * 1.3;
+            } else {
+                draw_aligned_text(&more, layout_x, layout_y, TextParams { color: LIGHT_GRAY, ..params });
+                break;
+            }
+        }
+
+        let (item, amount) = entry;
+        match item.texture {
+            Some(tex) => {
+                draw_texture_ex(tex, layout_x, layout_y - icon_size / 2.0, WHITE, DrawTextureParams { dest_size: Some(Vec2::new(icon_size, icon_size)), ..Default::default()});
+                layout_x += icon_size + px(2.0);
+            },
+            None => {
+                (layout_x, _) = draw_aligned_text(&format!("{} ", item.name()), layout_x, layout_y, params);
+            },
+        }
+        (layout_x, _) = draw_aligned_text(amount, layout_x, layout_y, TextParams { color: ORANGE, ..params });
+        layout_x += gap;
+    }
+    layout_y
+}
+
+// Returns whether the card was clicked and the ingredient under the mouse
+fn recipe_button(offer: &Offer, rect: Rect, selected: bool, per_cycle: bool, font_size: u16, font: Font, globe: Texture2D) -> (bool, Option<&Ingredients>) {
+    let mut hovered = None;
+    let recipe = &offer.recipe;
+    // Stacked cards have the image on the left of the text instead of above it
+    let wide = rect.w > rect.h;
 
     let mouse_in = rect.contains(input::mouse_position().into());
     let color = if selected { ORANGE } else {if mouse_in { GRAY } else { Color::from_rgba(0x00, 0x00, 0x00, 0x00) }};
@@ -148,60 +479,119 @@ fn recipe_button(recipe: &Recipe, offset_x: f32, selected: bool, font_size: u16,
     draw_rectangle(rect.x, rect.y, rect.w, rect.h, color);
 
     // Prepare layout
-    let mut layout_y = rect.y + rect.h / 2.0;
+    let mut layout_y = if wide { rect.y + font_size as f32 } else { rect.y + rect.h * 0.3 };
 
     // Image
     {
-        let image_sz = rect.w / 2.0;
-        let x = offset_x + rect.w / 2.0;
-        let y = rect.y + rect.h / 2.0;
+        let (image_sz, x, y) = if wide {
+            (rect.h * 0.8, rect.x + rect.h / 2.0, rect.y + rect.h / 2.0)
+        } else {
+            (rect.w / 2.0, rect.x + rect.w / 2.0, layout_y)
+        };
         draw_centered_texture(globe, x, y, image_sz, Color::from_rgba(0xff, 0xff, 0xff, 0x10));
-        if let Some(tex) = recipe.product.texture {
+        if let Some(tex) = recipe.product().name.texture {
             draw_centered_texture(tex, x, y, image_sz * 0.75, WHITE);
         }
-        layout_y += image_sz / 2.0 + 20.0;
+        if !wide {
+            layout_y += image_sz / 2.0 + px(20.0);
+        }
+
+        // Show what the standard recipe costs in place of the image on hover
+        if let (true, Some(default)) = (mouse_in, &offer.default) {
+            let panel = if wide {
+                let pad = rect.h / 20.0;
+                Rect::new(rect.x + pad, rect.y + pad, rect.w / 2.0 - pad * 2.0, rect.h - pad * 2.0)
+            } else {
+                let pad = rect.w / 10.0;
+                Rect::new(rect.x + pad, rect.y + pad, rect.w - pad * 2.0, y + image_sz / 2.0 - rect.y - pad)
+            };
+            draw_comparison(recipe, default, panel, font_size, font);
+        }
     }
 
-    let ingredient_size = rect.w / 10.0;
-    let mut layout_x = rect.x + ingredient_size;
+    // Left of the text column
+    let (ingredient_size, content_x) = if wide {
+        let size = ((rect.w - rect.h) / 10.0).min(rect.h / 5.0);
+        (size, rect.x + rect.h)
+    } else {
+        (rect.w / 10.0, rect.x + rect.w / 10.0)
+    };
+    let row_h = if wide { font_size as f32 * 1.6 } else { px(30.0) };
+    let layout_x = content_x;
 
-    // Recipe name
-    draw_aligned_text(&format!("Alternate Blueprint: {}", &recipe.name), layout_x, layout_y, TextParams { font_size, font, ..Default::default()});
-    layout_y += 15.0;
+    // Recipe name, wrapped to stay in the card
+    let name = format!("Alternate Blueprint: {}", &recipe.name);
+    layout_y = draw_wrapped_text(&name, layout_x, layout_y, rect.x + rect.w - content_x - px(10.0), 2, TextParams { font_size, font, ..Default::default()});
+    layout_y += px(15.0);
 
     // Ingredients 
     {
         let mut layout_x = layout_x;
+        let amount_y = layout_y + ingredient_size + font_size as f32 * 0.6;
+        let amount_params = TextParams { font_size: (font_size as f32 * 0.8) as u16, font, ..Default::default()};
         // In
         for input in &recipe.input {
-            draw_ingredient(&input.name, &mut layout_x, layout_y, ingredient_size);
+            let tile_x = layout_x;
+            if draw_ingredient(&input.name, &mut layout_x, layout_y, ingredient_size) {
+                hovered = Some(input);
+            }
+            if layout_x > tile_x {
+                draw_centered_text(&tile_amount(recipe, input, per_cycle), tile_x + ingredient_size / 2.0, amount_y, amount_params);
+            }
         }
 
         // Arrow
         let pad = ingredient_size / 4.0;
         draw_triangle(Vec2::new(layout_x, layout_y + pad), Vec2::new(layout_x, layout_y + ingredient_size - pad), Vec2::new(layout_x + pad * 1.414, layout_y + ingredient_size / 2.0), LIGHT_GRAY);
-        layout_x += pad * 1.414 + 5.0;
+        layout_x += pad * 1.414 + px(5.0);
 
         // Out
-        draw_ingredient(&recipe.product, &mut layout_x, layout_y, ingredient_size);
-        layout_y += ingredient_size + 20.0;
+        for output in &recipe.output {
+            let tile_x = layout_x;
+            if draw_ingredient(&output.name, &mut layout_x, layout_y, ingredient_size) {
+                hovered = Some(output);
+            }
+            if layout_x > tile_x {
+                draw_centered_text(&tile_amount(recipe, output, per_cycle), tile_x + ingredient_size / 2.0, amount_y, TextParams { color: ORANGE, ..amount_params });
+            }
+        }
+        layout_y += ingredient_size + font_size as f32 * 1.2 + row_h - px(10.0);
     }
 
-    (layout_x, _) = draw_aligned_text(&format!("Production Rate: "), layout_x, layout_y, TextParams { font_size, font, ..Default::default()});
-    draw_aligned_text(&format!("{} per minute", recipe.rate), layout_x, layout_y, TextParams { font_size, font, color: ORANGE, ..Default::default()});
+    let rates: Vec<(&Item, String)> = recipe.output.iter().map(|o| (&o.name, format_rate(&o.name, o.nb))).collect();
+    layout_y = draw_amount_row("Production Rate: ", &rates, layout_x, layout_y, rect.x + rect.w - px(10.0), 2, TextParams { font_size, font, ..Default::default()});
+    layout_y += row_h;
 
-    if input::is_mouse_button_released(MouseButton::Left) {
-        if mouse_in {
-            return true;
+    // Building
+    {
+        let mut layout_x = content_x;
+        let icon_size = font_size as f32 * 1.5;
+        draw_building(recipe.building, layout_x, layout_y, icon_size, font);
+        layout_x += icon_size + px(5.0);
+        (layout_x, _) = draw_aligned_text(&format!("{}: ", recipe.building.name()), layout_x, layout_y, TextParams { font_size, font, ..Default::default()});
+        (layout_x, _) = draw_aligned_text(&format!("{} MW", recipe.building.power()), layout_x, layout_y, TextParams { font_size, font, color: ORANGE, ..Default::default()});
+        if let Some(duration) = recipe.duration {
+            draw_wrapped_text(&format!(", {}s per craft", duration), layout_x, layout_y, rect.x + rect.w - layout_x - px(10.0), 1, TextParams { font_size, font, ..Default::default()});
         }
     }
-    false 
+    layout_y += row_h;
+
+    // Raw cost, on the lines left in the card
+    {
+        let lines = ((rect.y + rect.h - layout_y) / (font_size as f32 * 1.3)).floor().max(1.0) as usize;
+        let costs: Vec<(&Item, String)> = offer.raw_cost.iter().map(|r| (&r.name, format_amount(r.nb))).collect();
+        draw_amount_row("Raw cost per item: ", &costs, content_x, layout_y, rect.x + rect.w - px(10.0), lines, TextParams { font_size, font, ..Default::default()});
+    }
+
+    (input::is_mouse_button_released(MouseButton::Left) && mouse_in, hovered)
 }
 
-fn confirm_button(text_params: TextParams, checkmark: Texture2D, active: bool) -> bool {
-    let w = 200.0;
+fn confirm_button(text_params: TextParams, checkmark: Texture2D, active: bool, focused: bool) -> bool {
+    let w = px(200.0);
     let x = screen_width() / 2.0 - w / 2.0;
-    let rect = Rect {x, y: screen_height() - BORDER_SIZE, w, h: 50.0};
+    // Above the bottom bar in portrait, it's full of the other buttons
+    let y = if is_portrait() { screen_height() - bottom_bar() - px(50.0) } else { screen_height() - bottom_bar() };
+    let rect = Rect {x, y, w, h: px(50.0)};
 
     let mouse_in = active && rect.contains(input::mouse_position().into());
     let down = input::is_mouse_button_down(MouseButton::Left);
@@ -211,80 +601,529 @@ fn confirm_button(text_params: TextParams, checkmark: Texture2D, active: bool) -
     let mut text_params = text_params;
     text_params.color = if !active { LIGHT_GRAY } else { WHITE };
     draw_icon_text("Confirm", checkmark, rect.x + rect.w / 2.0, rect.y + rect.h / 2.0, Alignement::Center, text_params);
+    if focused {
+        draw_focus(rect);
+    }
 
-    if input::is_mouse_button_released(MouseButton::Left) {
-        if mouse_in {
-            return true;
-        }
+    input::is_mouse_button_released(MouseButton::Left) && mouse_in
+}
+
+// Button, usually in the bottom bar
+fn text_button(text: &str, x: f32, y: f32, w: f32, text_params: TextParams) -> bool {
+    let rect = Rect {x, y, w, h: px(50.0)};
+
+    let mouse_in = rect.contains(input::mouse_position().into());
+    let color = if mouse_in { LIGHT_GRAY } else { GRAY };
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, color);
+    let font_size = fit_font_size(text, rect.w - px(10.0), text_params);
+    draw_centered_text(text, rect.x + rect.w / 2.0, rect.y + rect.h / 2.0, TextParams { font_size, ..text_params });
+
+    input::is_mouse_button_released(MouseButton::Left) && mouse_in
+}
+
+// Small rounded toggle in the top bar, advances x past it.
+// Returns the mouse button released over it.
+fn chip(text: &str, x: &mut f32, y: f32, active: bool, text_params: TextParams) -> Option<MouseButton> {
+    let h = text_params.font_size as f32 * 2.0;
+    let measure = measure_text(text, Some(text_params.font), text_params.font_size, 1.0);
+    let rect = Rect::new(*x, y - h / 2.0, measure.width + px(20.0), h);
+
+    let mouse_in = rect.contains(input::mouse_position().into());
+    let color = if active { ORANGE } else if mouse_in { LIGHT_GRAY } else { GRAY };
+    draw_rounded_rectangle(rect.x, rect.y, rect.w, rect.h, px(5.0), color);
+    draw_aligned_text(text, rect.x + px(10.0), y, text_params);
+    *x += rect.w + px(10.0);
+
+    if !mouse_in {
+        return None;
+    }
+    [MouseButton::Left, MouseButton::Right].into_iter().find(|b| input::is_mouse_button_released(*b))
+}
+
+// Bottom left list of the validation warnings, returns true when clicked to dismiss it
+fn data_warnings_panel(warnings: &[String], font_size: u16, font: Font, icon: Texture2D) -> bool {
+    const MAX_LINES: usize = 8;
+    let line_h = font_size as f32 * 1.5;
+    let lines = warnings.len().min(MAX_LINES) + if warnings.len() > MAX_LINES { 1 } else { 0 };
+    let h = line_h * (lines as f32 + 1.0) + px(10.0);
+    let rect = Rect::new(px(10.0), screen_height() - bottom_bar() - h - px(10.0), screen_width() * 0.5, h);
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color { a: 0.95, ..DARK_GRAY });
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, px(2.0), ORANGE);
+
+    let params = TextParams { font, font_size, color: WHITE, ..Default::default() };
+    let mut y = rect.y + px(5.0) + line_h * 0.5;
+    draw_icon_text(&format!("{} problems in the recipe data (click to dismiss)", warnings.len()), icon, rect.x + px(10.0), y, Alignement::Left, TextParams { color: ORANGE, ..params });
+    for w in warnings.iter().take(MAX_LINES) {
+        y += line_h;
+        draw_aligned_text(w, rect.x + px(10.0), y, params);
+    }
+    if warnings.len() > MAX_LINES {
+        y += line_h;
+        draw_aligned_text(&format!("and {} more, see the log", warnings.len() - MAX_LINES), rect.x + px(10.0), y, TextParams { color: LIGHT_GRAY, ..params });
+    }
+
+    rect.contains(mouse_position().into()) && is_mouse_button_pressed(MouseButton::Left)
+}
+
+// Command line tools instead of the window:
+// `import-docs <Docs.json> [out]` regenerates the recipe list from the game files
+// `diff <old.json> <new.json> [--json]` lists the alternates that were added, removed or modified
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(|a| a.as_str()) {
+        Some("import-docs") => {
+            let Some(path) = args.get(1) else {
+                eprintln!("Usage: import-docs <Docs.json> [out, default res/recipes.json]");
+                std::process::exit(2);
+            };
+            let out = args.get(2).map(|a| a.as_str()).unwrap_or("res/recipes.json");
+            match docs::import(path) {
+                Ok(recipes) => {
+                    if let Err(e) = std::fs::write(out, serde_json::to_string(&recipes).unwrap() + "\n") {
+                        eprintln!("Unable to write {}: {}", out, e);
+                        std::process::exit(1);
+                    }
+                    println!("Wrote {} alternate recipes to {}", recipes.len(), out);
+                },
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                },
+            }
+        },
+        Some("diff") => {
+            let (Some(old), Some(new)) = (args.get(1), args.get(2)) else {
+                eprintln!("Usage: diff <old.json> <new.json> [--json]");
+                std::process::exit(2);
+            };
+            match (diff::load(old), diff::load(new)) {
+                (Ok(old), Ok(new)) => {
+                    let result = diff::RecipeDiff::new(&old, &new);
+                    if args.iter().any(|a| a == "--json") {
+                        println!("{}", serde_json::to_string_pretty(&result).unwrap());
+                    } else {
+                        print!("{}", result.to_text());
+                    }
+                },
+                (Err(e), _) | (_, Err(e)) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                },
+            }
+        },
+        _ => macroquad::Window::new("Satisfactory Alt Recipe", game()),
     }
-    false 
 }
 
-#[macroquad::main("Satisfactory Alt Recipe")]
-async fn main() {
+async fn game() {
+    // Problems in the recipe data, shown until dismissed
+    let mut recipes: Vec<Recipe> = serde_json::from_str(include_str!("../res/recipes.json")).unwrap();
+    let mut data_warnings = validate::validate(&recipes, validate::image_exists);
+    // Cards still work without the standard recipes, they just can't be compared
+    let mut defaults: DefaultRecipes = match serde_json::from_str(include_str!("../res/default_recipes.json")) {
+        Ok(defaults) => defaults,
+        Err(e) => {
+            data_warnings.push(format!("res/default_recipes.json: {}", e));
+            DefaultRecipes::new()
+        },
+    };
+    data_warnings.extend(validate::validate_defaults(&defaults, validate::image_exists));
+    for w in &data_warnings {
+        warn!("{}", w);
+    }
+    // Nothing to show for those
+    recipes.retain(|r| !r.output.is_empty());
+    defaults.retain(|_, r| !r.output.is_empty());
+    let mut res = Resources::new(recipes, defaults).await;
+    let mut show_data_warnings = !data_warnings.is_empty();
 
-    // do_the_scrape();
-    rand::srand(miniquad::date::now() as u64); 
+    // Links shared from the web build can set the seed, the draw and its three recipes
+    let mut seed = web::url_param("seed").and_then(|s| s.parse().ok()).unwrap_or(miniquad::date::now() as u64);
+    res.library.rng.srand(seed);
+    let mut draw: u32 = web::url_param("draw").and_then(|d| d.parse().ok()).unwrap_or(1).max(1);
+    let mut seed_field = SeedField::default();
+    let mut reseed: Option<u64> = None;
+    let mut link_copied_at: Option<f64> = None;
+    // Alternates picked so far when playing a campaign
+    let mut campaign: Option<Vec<Recipe>> = None;
+    let mut unlock: Option<String> = None;
+    let mut restart = false;
+    let mut unlocks = Unlocks::default();
 
-    let mut res = Resources::new().await;
+    let mut history = History::load();
+    let mut screen = Screen::Pick;
+    let mut history_screen = HistoryScreen::default();
+    let mut stats_screen = StatsScreen::default();
+    let mut ratings = Ratings::load();
+    let mut leaderboard_screen = LeaderboardScreen::default();
+    // Amounts on the cards per craft instead of per minute
+    let mut per_cycle = false;
+    let mut tooltip: Option<Tooltip> = None;
+    // Card the keyboard or gamepad is on, None until one of them is used
+    let mut focused: Option<usize> = None;
+    let mut gamepad = Gamepad::default();
+    let mut swipes = SwipeTracker::default();
+    let mut card_anim = CardAnimation::default();
 
     let mut selected_recipe: Option<u8> = None;
-    let mut displayed_recipes = select_recipes(&mut res.recipes, &mut res.item_textures).await;
-    let mut next = start_coroutine(async move { (select_recipes(&mut res.recipes, &mut res.item_textures).await, res.recipes, res.item_textures) });
+    // The draws before the shared one are skipped so the following ones come out the same
+    for _ in 1..draw {
+        draw_ids(&mut res.library);
+    }
+    let mut displayed_recipes = select_recipes(&mut res.library).await;
+    let shared_recipes: Vec<String> = web::url_param("recipes").map(|r| r.split(',').map(|n| n.trim().to_string()).collect()).unwrap_or_default();
+    let shared_ids = named_ids(&res.library.recipes, &shared_recipes);
+    if !shared_ids.is_empty() {
+        displayed_recipes = make_offers(&mut res.library, &shared_ids).await;
+    }
+    let mut next = start_coroutine(async move { (select_recipes(&mut res.library).await, res.library) });
     let mut show_next_when_ready = false;
 
     loop {
+        gamepad.update();
+        let swipe = swipes.update();
+        // Keys go to the seed field while it's being edited, checked before it can stop editing this frame
+        let typing = seed_field.editing();
+        // Follow the window size
+        res.font_med = layout::font_size(15.0);
+        res.font_big = layout::font_size(20.0);
         clear_background(BLACK);
         // Background image + blur
         draw_texture_ex(res.mam, 0.0, 0.0, WHITE, DrawTextureParams {dest_size: Some(Vec2::new(screen_width(), screen_height())), ..Default::default()});
         draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::from_rgba(0x0d, 0x0d, 0x0d, 0xf0));
         
         // Top/Bottom Borders
-        draw_rectangle(0.0, 0.0, screen_width(), BORDER_SIZE, DARK_GRAY);
-        draw_rectangle(0.0, screen_height() - BORDER_SIZE, screen_width(), BORDER_SIZE, DARK_GRAY);
+        draw_rectangle(0.0, 0.0, screen_width(), top_bar(), DARK_GRAY);
+        draw_rectangle(0.0, screen_height() - bottom_bar(), screen_width(), bottom_bar(), DARK_GRAY);
 
         // Top text
-        draw_icon_text("Analysis Complete!", res.warning_icon, 10.0, BORDER_SIZE / 2.0, Alignement::Left, TextParams {font:res.font, font_size: res.font_big, ..Default::default()});
+        let top_params = TextParams {font:res.font, font_size: res.font_med, ..Default::default()};
+        if !is_portrait() {
+            draw_icon_text("Analysis Complete!", res.warning_icon, px(10.0), top_bar_row(0), Alignement::Left, TextParams {font:res.font, font_size: res.font_big, ..Default::default()});
+        }
+        let seed_params = if is_portrait() { top_params } else { TextParams {font:res.font, font_size: res.font_big, ..Default::default()} };
+        if let Some(new_seed) = seed_field.draw(seed, draw, top_bar_row(1), seed_params) {
+            reseed = Some(new_seed);
+            restart = true;
+        }
+        let unlocks_x = if is_portrait() { px(10.0) } else { screen_width() * 0.25 };
+        if unlocks_field(&mut unlocks, unlocks_x, top_bar_row(0), top_params) {
+            restart = true;
+        }
+        let mut tabs_x = if is_portrait() { px(10.0) } else { screen_width() * 0.55 };
+        let tabs_y = top_bar_row(1);
+        if chip("History", &mut tabs_x, tabs_y, screen == Screen::History, TextParams {font:res.font, font_size: res.font_med, ..Default::default()}).is_some() {
+            screen = if screen == Screen::History { Screen::Pick } else { Screen::History };
+        }
+        if chip("Stats", &mut tabs_x, tabs_y, screen == Screen::Stats, TextParams {font:res.font, font_size: res.font_med, ..Default::default()}).is_some() {
+            screen = if screen == Screen::Stats { Screen::Pick } else { Screen::Stats };
+            load_recipe_icons(&mut res.recipe_icons, &mut res.ui_textures).await;
+        }
+        if chip("Leaderboard", &mut tabs_x, tabs_y, screen == Screen::Leaderboard, TextParams {font:res.font, font_size: res.font_med, ..Default::default()}).is_some() {
+            screen = if screen == Screen::Leaderboard { Screen::Pick } else { Screen::Leaderboard };
+            load_recipe_icons(&mut res.recipe_icons, &mut res.ui_textures).await;
+        }
+
+        if screen == Screen::History {
+            draw_header(&format!("{} hard drives analyzed", history.picks.len()), res.font_big, res.font);
+            history_screen.draw(&history, res.font_med, res.font);
+        } else if screen == Screen::Stats {
+            let stats = stats_screen.stats(&history);
+            draw_header(&format!("{} alternates offered", stats.recipes.len()), res.font_big, res.font);
+            stats_screen.draw(&stats, &res.recipe_icons, res.font_med, res.font);
+        } else if screen == Screen::Leaderboard {
+            draw_header("Alternate recipes ranked by the team's picks", res.font_big, res.font);
+            leaderboard_screen.draw(&ratings, &res.recipe_icons, res.font_med, res.font);
+        } else {
+            let campaign_over = campaign.is_some() && displayed_recipes.is_empty();
+            let count = displayed_recipes.len();
 
-        for (i, recipe) in displayed_recipes.iter().enumerate() {
-            let is_selected = if let Some(r) = selected_recipe { r == i as u8 } else { false };
-            if recipe_button(recipe, i as f32 * screen_width() / 3.0, is_selected, res.font_med, res.font, res.globe) {
-                selected_recipe = Some(i as u8);
+            let hovered_card = (0..count).find(|i| card_rect(*i, count).contains(input::mouse_position().into()));
+            card_anim.update(get_frame_time(), hovered_card);
+            // The cards can't be picked before they show up
+            let scanning = card_anim.scanning().filter(|_| !campaign_over);
+
+            // Keyboard and gamepad: arrows / D-pad move the focus, 1-2-3 select a card,
+            // Enter / Space / A select the focused card then confirm it
+            let mut confirm_pressed = false;
+            if !typing && count > 0 && scanning.is_none() {
+                focused = focused.map(|f| f.min(count - 1));
+                let previous = is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::Up) || gamepad.is_pressed(Button::DpadLeft) || gamepad.is_pressed(Button::DpadUp);
+                let next = is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::Down) || gamepad.is_pressed(Button::DpadRight) || gamepad.is_pressed(Button::DpadDown);
+                if previous {
+                    focused = Some(focused.map_or(count - 1, |f| (f + count - 1) % count));
+                }
+                if next {
+                    focused = Some(focused.map_or(0, |f| (f + 1) % count));
+                }
+                for (i, key) in [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3].into_iter().enumerate().take(count) {
+                    if is_key_pressed(key) || is_key_pressed([KeyCode::Kp1, KeyCode::Kp2, KeyCode::Kp3][i]) {
+                        focused = Some(i);
+                        selected_recipe = Some(i as u8);
+                    }
+                }
+                if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) || is_key_pressed(KeyCode::Space) || gamepad.is_pressed(Button::A) {
+                    match focused {
+                        Some(f) if selected_recipe != Some(f as u8) => selected_recipe = Some(f as u8),
+                        _ => confirm_pressed = true,
+                    }
+                }
             }
-        }
 
-        draw_centered_text("The analysis of Hard Drive is completed! Select your desired reward.", screen_width() / 2.0, BORDER_SIZE + 25.0, TextParams { font: res.font, font_size: res.font_big, color:WHITE, ..Default::default()});
+            // Swiping towards the next card selects it, cards are stacked in portrait
+            if count > 0 && scanning.is_none() {
+                let step = match (swipe, is_portrait()) {
+                    (Some(Swipe::Left), false) | (Some(Swipe::Up), true) => Some(1),
+                    (Some(Swipe::Right), false) | (Some(Swipe::Down), true) => Some(count - 1),
+                    _ => None,
+                };
+                if let Some(step) = step {
+                    selected_recipe = Some(selected_recipe.map_or(0, |r| (r as usize + step) % count) as u8);
+                    focused = focused.and(selected_recipe.map(|r| r as usize));
+                }
+            }
+
+            if let Some(progress) = scanning {
+                draw_scan(progress, res.globe, res.font_med, res.font);
+            }
+            for (i, offer) in displayed_recipes.iter().enumerate().filter(|(i, _)| scanning.is_none() && card_anim.reveal(*i) > 0.0) {
+                let is_selected = if let Some(r) = selected_recipe { r == i as u8 } else { false };
+                let rect = card_anim.card_rect(i, card_rect(i, count));
+                let (clicked, hovered) = recipe_button(offer, rect, is_selected, per_cycle, res.font_med, res.font, res.globe);
+                if focused == Some(i) && !is_selected {
+                    draw_focus(rect);
+                }
+                let flash = card_anim.flash(i);
+                if flash > 0.0 {
+                    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(1.0, 1.0, 1.0, flash * 0.6));
+                }
+                // The end of a swipe also comes as a click
+                if clicked && swipe.is_none() {
+                    selected_recipe = Some(i as u8);
+                    focused = focused.map(|_| i);
+                }
+                if let Some(ingredient) = hovered {
+                    tooltip = Some(item_tooltip(&ingredient.name, ingredient.nb, &offer.recipe.name, &res.item_uses));
+                }
+            }
+            // The cards slide in from under the bottom bar
+            if !card_anim.revealed(count) {
+                draw_rectangle(0.0, screen_height() - bottom_bar(), screen_width(), bottom_bar(), DARK_GRAY);
+            }
+            if displayed_recipes.is_empty() && !campaign_over && scanning.is_none() {
+                draw_centered_text("This hard drive is empty, no alternate recipe matches the selected tier and research.", screen_width() / 2.0, screen_height() / 2.0, TextParams { font: res.font, font_size: res.font_big, color: LIGHT_GRAY, ..Default::default()});
+            }
 
-        if confirm_button(TextParams { font: res.font, font_size: res.font_big, ..Default::default()}, res.checkmark, selected_recipe.is_some()) {
-            show_next_when_ready = true;
+            if let (true, Some(unlocked)) = (campaign_over, &campaign) {
+                draw_header(&format!("Campaign complete! {} alternates unlocked.", unlocked.len()), res.font_big, res.font);
+                campaign_summary(unlocked, res.font_med, res.font);
+
+                // Where Confirm would be
+                let y = if is_portrait() { screen_height() - bottom_bar() - px(50.0) } else { screen_height() - bottom_bar() };
+                if text_button("New campaign", screen_width() / 2.0 - px(100.0), y, px(200.0), TextParams { font: res.font, font_size: res.font_big, color: WHITE, ..Default::default()}) {
+                    campaign = Some(Vec::new());
+                    restart = true;
+                }
+            } else {
+                if scanning.is_some() {
+                    draw_header("Analyzing Hard Drive...", res.font_big, res.font);
+                } else {
+                    draw_header("The analysis of Hard Drive is completed! Select your desired reward.", res.font_big, res.font);
+                }
+
+                let can_confirm = selected_recipe.is_some() && !show_next_when_ready && scanning.is_none();
+                // Once the focused card is selected the next press confirms
+                let confirm_focused = focused.is_some() && focused.map(|f| f as u8) == selected_recipe;
+                if confirm_button(TextParams { font: res.font, font_size: res.font_big, ..Default::default()}, res.checkmark, can_confirm, confirm_focused) && swipe.is_none() || (confirm_pressed && can_confirm) {
+                    if let Some(i) = selected_recipe {
+                        card_anim.confirm(i as usize);
+                        let picked = &displayed_recipes[i as usize].recipe;
+                        let offered: Vec<String> = displayed_recipes.iter().map(|o| o.recipe.name.clone()).collect();
+                        ratings.record(&picked.name, &offered);
+                        history.record(Pick {
+                            timestamp: miniquad::date::now(),
+                            seed,
+                            draw,
+                            offered,
+                            chosen: picked.name.clone(),
+                        });
+                        if let Some(unlocked) = &mut campaign {
+                            unlocked.push(picked.clone());
+                            unlock = Some(picked.name.clone());
+                        }
+                    }
+                    show_next_when_ready = true;
+                }
+            }
+
+            // Side buttons of the bottom bar, they share its whole width in portrait where Confirm is above it
+            let bar_y = screen_height() - bottom_bar();
+            let (side_w, campaign_x, amounts_x, link_x) = if is_portrait() {
+                let w = (screen_width() - px(40.0)) / 3.0;
+                (w, px(10.0), px(20.0) + w, px(30.0) + w * 2.0)
+            } else {
+                (px(200.0), px(20.0), px(240.0), screen_width() - px(220.0))
+            };
+
+            let campaign_text = match &campaign {
+                Some(unlocked) => format!("Campaign: {} unlocked", unlocked.len()),
+                None => "Start campaign".to_string(),
+            };
+            if text_button(&campaign_text, campaign_x, bar_y, side_w, TextParams { font: res.font, font_size: res.font_med, color: WHITE, ..Default::default()}) {
+                campaign = if campaign.is_some() { None } else { Some(Vec::new()) };
+                restart = true;
+            }
+
+            let amounts_text = if per_cycle { "Amounts: per craft" } else { "Amounts: per minute" };
+            if text_button(amounts_text, amounts_x, bar_y, side_w, TextParams { font: res.font, font_size: res.font_med, color: WHITE, ..Default::default()}) {
+                per_cycle = !per_cycle;
+            }
+
+            let copied = link_copied_at.is_some_and(|t| get_time() - t < 2.0);
+            let link_text = if copied { "Link copied!" } else { "Copy link" };
+            if text_button(link_text, link_x, bar_y, side_w, TextParams { font: res.font, font_size: res.font_med, color: WHITE, ..Default::default()}) {
+                let names: Vec<&str> = displayed_recipes.iter().map(|o| o.recipe.name.as_str()).collect();
+                web::copy_link(&[("seed", seed.to_string()), ("draw", draw.to_string()), ("recipes", names.join(","))]);
+                link_copied_at = Some(get_time());
+            }
         }
 
-        if show_next_when_ready && next.is_done() {
-            (displayed_recipes, res.recipes, res.item_textures) = next.retrieve().unwrap();
-            next = start_coroutine(async move { (select_recipes(&mut res.recipes, &mut res.item_textures).await, res.recipes, res.item_textures) });
+        if show_next_when_ready && next.is_done() && !card_anim.flashing() {
+            (displayed_recipes, res.library) = next.retrieve().unwrap();
+            // The pending draw was made before the pick, it may still offer it
+            if let Some(name) = unlock.take() {
+                res.library.excluded.push(name);
+                if displayed_recipes.iter().any(|o| res.library.excluded.contains(&o.recipe.name)) {
+                    displayed_recipes = select_recipes(&mut res.library).await;
+                }
+            }
+            next = start_coroutine(async move { (select_recipes(&mut res.library).await, res.library) });
             selected_recipe = None;
             show_next_when_ready = false;
+            card_anim.restart();
+            draw += 1;
+        }
+
+        // Throw away the pending draw and restart the sequence, from a new seed or in another mode
+        if restart && next.is_done() {
+            (_, res.library) = next.retrieve().unwrap();
+            if let Some(new_seed) = reseed.take() {
+                seed = new_seed;
+                if let Some(unlocked) = &mut campaign {
+                    unlocked.clear();
+                }
+            }
+            res.library.rng.srand(seed);
+            // A campaign going on keeps what it already unlocked out of the draws
+            res.library.excluded = campaign.iter().flatten().map(|r| r.name.clone()).collect();
+            res.library.unlocks = unlocks.clone();
+            unlock = None;
+            restart = false;
+            displayed_recipes = select_recipes(&mut res.library).await;
+            next = start_coroutine(async move { (select_recipes(&mut res.library).await, res.library) });
+            selected_recipe = None;
+            show_next_when_ready = false;
+            card_anim.restart();
+            draw = 1;
+        }
+
+        if let Some(t) = tooltip.take() {
+            draw_tooltip(&t, res.font_med, res.font);
+        }
+        if show_data_warnings && data_warnings_panel(&data_warnings, res.font_med, res.font, res.warning_icon) {
+            show_data_warnings = false;
         }
         next_frame().await;
     }
 }
 
 
-async fn select_recipes(recipes: &mut Vec<Recipe>, texs: &mut ItemTextureMap) -> Vec<Recipe> {
+// Offers up to three recipes, fewer when there aren't enough eligible ones left
+async fn select_recipes(lib: &mut Library) -> Vec<Offer> {
+    let ids = draw_ids(lib);
+    make_offers(lib, &ids).await
+}
+
+// Indices in lib.recipes of the next draw
+fn draw_ids(lib: &mut Library) -> Vec<usize> {
+    let eligible: Vec<usize> = (0..lib.recipes.len())
+        .filter(|i| lib.unlocks.allows(&lib.recipes[*i]) && !lib.excluded.contains(&lib.recipes[*i].name))
+        .collect();
+    draw_distinct(&mut lib.rng, eligible.len(), 3).into_iter().map(|i| eligible[i]).collect()
+}
+
+// `count` different indices below `n`, or all of them in a random order when there are fewer
+fn draw_distinct(rng: &mut RandGenerator, n: usize, count: usize) -> Vec<usize> {
+    let count = n.min(count);
     let mut ids = Vec::new();
-    loop {
-        let nb = rand::rand() as usize % recipes.len();
-        if ids.contains(&nb) {
-            continue;
+    while ids.len() < count {
+        let nb = rng.rand() as usize % n;
+        if !ids.contains(&nb) {
+            ids.push(nb);
         }
-        ids.push(nb);
-        if ids.len() == 3 {
-            break;
+    }
+    ids
+}
+
+// The first three different known names, unknown names are skipped
+fn named_ids(recipes: &[Recipe], names: &[String]) -> Vec<usize> {
+    let mut ids = Vec::new();
+    for i in names.iter().filter_map(|n| recipes.iter().position(|r| &r.name == n)) {
+        if ids.len() < 3 && !ids.contains(&i) {
+            ids.push(i);
         }
     }
-    for i in &ids {
-        recipes[*i].load(texs).await;
+    ids
+}
+
+async fn make_offers(lib: &mut Library, ids: &[usize]) -> Vec<Offer> {
+    let mut result = Vec::new();
+    for i in ids {
+        let mut raw_cost = RecipeGraph::new(&lib.defaults, &lib.recipes).raw_cost(&lib.recipes[*i]);
+        for raw in &mut raw_cost {
+            raw.name.load(&mut lib.item_textures).await;
+        }
+
+        let recipe = &mut lib.recipes[*i];
+        recipe.load(&mut lib.item_textures).await;
+        let default = match lib.defaults.get_mut(recipe.product().name.name()) {
+            Some(default) => {
+                default.load(&mut lib.item_textures).await;
+                Some(default.clone())
+            },
+            None => None,
+        };
+        result.push(Offer { recipe: recipe.clone(), default, raw_cost });
     }
-    ids.iter().map(|i| recipes[*i].clone()).collect()
+    result
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_up_to_three_distinct_recipes() {
+        let mut rng = RandGenerator::new();
+        for seed in 0..20 {
+            rng.srand(seed);
+            for n in 0..6 {
+                let mut ids = draw_distinct(&mut rng, n, 3);
+                assert_eq!(ids.len(), n.min(3));
+                assert!(ids.iter().all(|i| *i < n));
+                ids.sort();
+                ids.dedup();
+                assert_eq!(ids.len(), n.min(3));
+            }
+        }
+    }
+
+    #[test]
+    fn shared_names_keep_three_known_ones() {
+        let recipes: Vec<Recipe> = serde_json::from_str(include_str!("../res/recipes.json")).unwrap();
+        let names: Vec<String> = [&recipes[4].name, "Nope", &recipes[1].name, &recipes[4].name, &recipes[2].name, &recipes[3].name]
+            .iter().map(|n| n.to_string()).collect();
+        assert_eq!(named_ids(&recipes, &names), vec![4, 1, 2]);
+        assert!(named_ids(&recipes, &["Nope".to_string()]).is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::storage;
+
+const STORAGE_KEY: &str = "ratings";
+
+pub const INITIAL_RATING: f32 = 1500.0;
+// How much a single matchup can move a rating
+const K_FACTOR: f32 = 32.0;
+
+// Elo score per recipe name. A pick counts as the chosen recipe beating each of the others.
+#[derive(Debug, Default)]
+pub struct Ratings {
+    scores: HashMap<String, f32>,
+}
+
+impl Ratings {
+    pub fn load() -> Self {
+        let scores = storage::load(STORAGE_KEY).and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default();
+        Self { scores }
+    }
+
+    fn save(&self) {
+        match serde_json::to_string(&self.scores) {
+            Ok(s) => storage::save(STORAGE_KEY, &s),
+            Err(e) => macroquad::logging::error!("Unable to save the ratings: {}", e),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> f32 {
+        self.scores.get(name).copied().unwrap_or(INITIAL_RATING)
+    }
+
+    pub fn record(&mut self, chosen: &str, offered: &[String]) {
+        self.update(chosen, offered);
+        self.save();
+    }
+
+    fn update(&mut self, chosen: &str, offered: &[String]) {
+        // All the matchups of a pick use the ratings from before it
+        let winner_rating = self.get(chosen);
+        let mut winner_delta = 0.0;
+        for loser in offered.iter().filter(|n| *n != chosen) {
+            let loser_rating = self.get(loser);
+            let expected = 1.0 / (1.0 + 10f32.powf((loser_rating - winner_rating) / 400.0));
+            let delta = K_FACTOR * (1.0 - expected);
+            winner_delta += delta;
+            self.scores.insert(loser.clone(), loser_rating - delta);
+        }
+        self.scores.insert(chosen.to_string(), winner_rating + winner_delta);
+    }
+
+    // Best first, recipes that were never offered keep the initial rating
+    pub fn leaderboard<'a>(&self, names: impl Iterator<Item = &'a String>) -> Vec<(String, f32)> {
+        let mut result: Vec<(String, f32)> = names.map(|n| (n.clone(), self.get(n))).collect();
+        result.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn pick_among_equals() {
+        let mut ratings = Ratings::default();
+        let offered = names(&["Cast Screw", "Iron Wire", "Steel Rod"]);
+        ratings.update("Iron Wire", &offered);
+        assert_eq!(ratings.get("Iron Wire"), INITIAL_RATING + 32.0);
+        assert_eq!(ratings.get("Cast Screw"), INITIAL_RATING - 16.0);
+        assert_eq!(ratings.get("Steel Rod"), INITIAL_RATING - 16.0);
+
+        // Whatever the winner gains the losers lose
+        ratings.update("Steel Rod", &offered);
+        let total: f32 = offered.iter().map(|n| ratings.get(n)).sum();
+        assert!((total - 3.0 * INITIAL_RATING).abs() < 1e-2);
+    }
+
+    #[test]
+    fn leaderboard_order() {
+        let mut ratings = Ratings::default();
+        ratings.update("Steel Rod", &names(&["Steel Rod", "Cast Screw"]));
+        let all = names(&["Iron Wire", "Cast Screw", "Bolted Frame", "Steel Rod"]);
+        let order: Vec<String> = ratings.leaderboard(all.iter()).into_iter().map(|(n, _)| n).collect();
+        // Untouched ones tie at the initial rating, by name
+        assert_eq!(order, names(&["Steel Rod", "Bolted Frame", "Iron Wire", "Cast Screw"]));
+    }
+}
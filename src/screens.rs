@@ -0,0 +1,304 @@
+// Everything drawn instead of the cards (history, stats, leaderboard, campaign summary) and the top bar fields
+
+use std::collections::HashMap;
+
+use macroquad::{prelude::*, input};
+
+use satisfactory_alt_recipe::items::*;
+
+use crate::{history::*, stats::*, rating::*};
+use crate::layout::{px, top_bar, bottom_bar};
+use crate::{draw_aligned_text, draw_centered_text, draw_ingredient, draw_rounded_rectangle, chip};
+use crate::{DARK_GRAY, GRAY, LIGHT_GRAY, WHITE, ORANGE, GREEN, RED};
+
+// Every alternate unlocked during the campaign, in the order they were picked
+pub fn campaign_summary(unlocked: &[Recipe], font_size: u16, font: Font) {
+    let columns = 4;
+    let rows = unlocked.len().div_ceil(columns).max(1);
+    let top = top_bar() + px(60.0);
+    let column_w = (screen_width() - px(40.0)) / columns as f32;
+    let row_h = ((screen_height() - bottom_bar() - px(20.0) - top) / rows as f32).min(font_size as f32 * 2.0);
+    let font_size = font_size.min((row_h * 0.6) as u16);
+
+    for (i, recipe) in unlocked.iter().enumerate() {
+        let mut x = px(20.0) + (i / rows) as f32 * column_w;
+        let y = top + (i % rows) as f32 * row_h;
+        draw_ingredient(&recipe.product().name, &mut x, y, row_h - px(4.0));
+        draw_aligned_text(&recipe.name, x, y + row_h / 2.0 - px(2.0), TextParams { font_size, font, ..Default::default()});
+    }
+}
+
+// Tier and MAM research toggles in the top bar, starting at x.
+// Left click on the tier goes up, right click goes down. Returns true when something changed.
+pub fn unlocks_field(unlocks: &mut Unlocks, x: f32, y: f32, text_params: TextParams) -> bool {
+    let mut layout_x = x;
+    let mut changed = false;
+
+    match chip(&format!("Tier {}", unlocks.tier), &mut layout_x, y, false, text_params) {
+        Some(MouseButton::Left) => {
+            unlocks.tier = (unlocks.tier + 1) % (MAX_TIER + 1);
+            changed = true;
+        },
+        Some(_) => {
+            unlocks.tier = (unlocks.tier + MAX_TIER) % (MAX_TIER + 1);
+            changed = true;
+        },
+        None => {},
+    }
+
+    for research in RESEARCHES {
+        let pos = unlocks.research.iter().position(|r| r == research);
+        if chip(research, &mut layout_x, y, pos.is_some(), text_params).is_some() {
+            match pos {
+                Some(i) => { unlocks.research.remove(i); },
+                None => unlocks.research.push(research.to_string()),
+            }
+            changed = true;
+        }
+    }
+    changed
+}
+
+// Wheel notches move by `step`, whatever the platform reports as a notch
+fn scroll_with_wheel(scroll: &mut f32, content_h: f32, view_h: f32, step: f32) {
+    let wheel = input::mouse_wheel().1;
+    if wheel != 0.0 {
+        *scroll -= wheel.signum() * step;
+    }
+    *scroll = scroll.clamp(0.0, (content_h - view_h).max(0.0));
+}
+
+// Past decisions, newest first. Scrolls with the mouse wheel.
+#[derive(Debug, Default)]
+pub struct HistoryScreen {
+    scroll: f32,
+}
+
+impl HistoryScreen {
+    pub fn draw(&mut self, history: &History, font_size: u16, font: Font) {
+        let top = top_bar() + px(60.0);
+        let bottom = screen_height() - bottom_bar() - px(10.0);
+        let row_h = font_size as f32 * 2.0;
+
+        if history.picks.is_empty() {
+            draw_centered_text("No hard drive analyzed yet.", screen_width() / 2.0, screen_height() / 2.0, TextParams { font, font_size, color: LIGHT_GRAY, ..Default::default()});
+            return;
+        }
+
+        scroll_with_wheel(&mut self.scroll, history.picks.len() as f32 * row_h, bottom - top, row_h * 3.0);
+
+        for (i, pick) in history.picks.iter().rev().enumerate() {
+            let y = top + i as f32 * row_h - self.scroll + row_h / 2.0;
+            if y < top || y > bottom {
+                continue;
+            }
+            let mut x = px(40.0);
+            (x, _) = draw_aligned_text(&format!("{}   Seed {} #{}   ", format_timestamp(pick.timestamp), pick.seed, pick.draw), x, y, TextParams { font, font_size, color: LIGHT_GRAY, ..Default::default()});
+            for (j, name) in pick.offered.iter().enumerate() {
+                if j > 0 {
+                    (x, _) = draw_aligned_text("  /  ", x, y, TextParams { font, font_size, color: GRAY, ..Default::default()});
+                }
+                let color = if *name == pick.chosen { ORANGE } else { WHITE };
+                (x, _) = draw_aligned_text(name, x, y, TextParams { font, font_size, color, ..Default::default()});
+            }
+        }
+    }
+}
+
+
+// Per recipe table on the left, click a header to sort or a row to see its head-to-head on the right
+#[derive(Debug)]
+pub struct StatsScreen {
+    sort: SortBy,
+    selected: Option<String>,
+    scroll: f32,
+}
+
+impl Default for StatsScreen {
+    fn default() -> Self {
+        StatsScreen { sort: SortBy::Picked, selected: None, scroll: 0.0 }
+    }
+}
+
+impl StatsScreen {
+    // In the order picked on the screen
+    pub fn stats(&self, history: &History) -> Stats {
+        let mut stats = Stats::from_picks(&history.picks);
+        stats.sort(self.sort);
+        stats
+    }
+
+    pub fn draw(&mut self, stats: &Stats, icons: &HashMap<String, Item>, font_size: u16, font: Font) {
+        if stats.recipes.is_empty() {
+            draw_centered_text("No hard drive analyzed yet.", screen_width() / 2.0, screen_height() / 2.0, TextParams { font, font_size, color: LIGHT_GRAY, ..Default::default()});
+            return;
+        }
+
+        let top = top_bar() + px(60.0);
+        let bottom = screen_height() - bottom_bar() - px(10.0);
+        let row_h = font_size as f32 * 2.0;
+        let table_w = screen_width() * 0.6;
+        let columns = [
+            ("Recipe", px(40.0), SortBy::Name),
+            ("Offered", table_w * 0.6, SortBy::Offered),
+            ("Picked", table_w * 0.75, SortBy::Picked),
+            ("Pick rate", table_w * 0.9, SortBy::PickRate),
+        ];
+
+        // Headers
+        let header_y = top + row_h / 2.0;
+        for (title, x, by) in columns {
+            let color = if self.sort == by { ORANGE } else { LIGHT_GRAY };
+            let (end_x, _) = draw_aligned_text(title, x, header_y, TextParams { font, font_size, color, ..Default::default()});
+            let rect = Rect::new(x, top, end_x - x, row_h);
+            if input::is_mouse_button_released(MouseButton::Left) && rect.contains(input::mouse_position().into()) {
+                self.sort = by;
+            }
+        }
+
+        // Rows
+        let rows_top = top + row_h;
+        scroll_with_wheel(&mut self.scroll, stats.recipes.len() as f32 * row_h, bottom - rows_top, row_h * 3.0);
+        for (i, recipe) in stats.recipes.iter().enumerate() {
+            let y = rows_top + i as f32 * row_h - self.scroll;
+            if y < rows_top || y + row_h > bottom {
+                continue;
+            }
+            let rect = Rect::new(px(20.0), y, table_w, row_h);
+            let is_selected = self.selected.as_deref() == Some(recipe.name.as_str());
+            if is_selected || rect.contains(input::mouse_position().into()) {
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, if is_selected { GRAY } else { DARK_GRAY });
+            }
+            if input::is_mouse_button_released(MouseButton::Left) && rect.contains(input::mouse_position().into()) {
+                self.selected = Some(recipe.name.clone());
+            }
+
+            let mut x = columns[0].1;
+            if let Some(icon) = icons.get(&recipe.name) {
+                draw_ingredient(icon, &mut x, y + px(2.0), row_h - px(4.0));
+            }
+            let text_y = y + row_h / 2.0;
+            draw_aligned_text(&recipe.name, x, text_y, TextParams { font, font_size, ..Default::default()});
+            draw_aligned_text(&recipe.offered.to_string(), columns[1].1, text_y, TextParams { font, font_size, ..Default::default()});
+            draw_aligned_text(&recipe.picked.to_string(), columns[2].1, text_y, TextParams { font, font_size, ..Default::default()});
+            draw_aligned_text(&format!("{:.0}%", recipe.pick_rate() * 100.0), columns[3].1, text_y, TextParams { font, font_size, color: ORANGE, ..Default::default()});
+        }
+
+        // Head-to-head
+        let Some(name) = self.selected.as_deref() else { return };
+        let x = table_w + px(60.0);
+        let mut y = header_y;
+        draw_aligned_text(&format!("Head-to-head: {}", name), x, y, TextParams { font, font_size, ..Default::default()});
+        y += row_h;
+        for (opponent, wins, losses) in stats.matchups(name) {
+            if y + row_h > bottom {
+                break;
+            }
+            let (end_x, _) = draw_aligned_text(&format!("vs {}   ", opponent), x, y, TextParams { font, font_size, color: LIGHT_GRAY, ..Default::default()});
+            let color = if wins > losses { GREEN } else if wins < losses { RED } else { WHITE };
+            draw_aligned_text(&format!("{} - {}", wins, losses), end_x, y, TextParams { font, font_size, color, ..Default::default()});
+            y += row_h;
+        }
+    }
+}
+
+
+// Every alternate ranked by rating, scrolls with the mouse wheel
+#[derive(Debug, Default)]
+pub struct LeaderboardScreen {
+    scroll: f32,
+}
+
+impl LeaderboardScreen {
+    pub fn draw(&mut self, ratings: &Ratings, icons: &HashMap<String, Item>, font_size: u16, font: Font) {
+        let top = top_bar() + px(60.0);
+        let bottom = screen_height() - bottom_bar() - px(10.0);
+        let row_h = font_size as f32 * 2.0;
+        let x = screen_width() / 2.0 - screen_width() * 0.2;
+        let rating_x = screen_width() / 2.0 + screen_width() * 0.2;
+
+        let leaderboard = ratings.leaderboard(icons.keys());
+        scroll_with_wheel(&mut self.scroll, leaderboard.len() as f32 * row_h, bottom - top, row_h * 3.0);
+        for (i, (name, rating)) in leaderboard.iter().enumerate() {
+            let y = top + i as f32 * row_h - self.scroll;
+            if y < top || y + row_h > bottom {
+                continue;
+            }
+            let text_y = y + row_h / 2.0;
+            let mut layout_x = x;
+            draw_aligned_text(&format!("{}.", i + 1), layout_x - font_size as f32 * 3.0, text_y, TextParams { font, font_size, color: LIGHT_GRAY, ..Default::default()});
+            if let Some(icon) = icons.get(name) {
+                draw_ingredient(icon, &mut layout_x, y + px(2.0), row_h - px(4.0));
+            }
+            draw_aligned_text(name, layout_x, text_y, TextParams { font, font_size, ..Default::default()});
+            let color = if *rating > INITIAL_RATING { GREEN } else if *rating < INITIAL_RATING { RED } else { WHITE };
+            draw_aligned_text(&format!("{:.0}", rating), rating_x, text_y, TextParams { font, font_size, color, ..Default::default()});
+        }
+    }
+}
+
+// Shows the seed on the right of the top bar, click on it to type another one
+#[derive(Debug, Default)]
+pub struct SeedField {
+    editing: Option<String>,
+}
+
+impl SeedField {
+    pub fn editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    // Returns the typed seed when Enter is pressed
+    pub fn draw(&mut self, seed: u64, draw: u32, y: f32, text_params: TextParams) -> Option<u64> {
+        let text = match &self.editing {
+            Some(typed) => format!("Seed: {}_", typed),
+            None => format!("Seed: {}  #{}", seed, draw),
+        };
+        let measure = measure_text(&text, Some(text_params.font), text_params.font_size, 1.0);
+        let x = screen_width() - measure.width - px(20.0);
+        let rect = Rect::new(x - px(10.0), y - text_params.font_size as f32, measure.width + px(20.0), text_params.font_size as f32 * 2.0);
+
+        let mouse_in = rect.contains(input::mouse_position().into());
+        if self.editing.is_some() || mouse_in {
+            draw_rounded_rectangle(rect.x, rect.y, rect.w, rect.h, px(5.0), GRAY);
+        }
+        let mut text_params = text_params;
+        text_params.color = if self.editing.is_some() { ORANGE } else { WHITE };
+        draw_aligned_text(&text, x, y, text_params);
+
+        // macroquad never empties its queue of typed characters, the ones typed before editing (card shortcuts...) are dropped
+        let mut chars = Vec::new();
+        while let Some(c) = input::get_char_pressed() {
+            chars.push(c);
+        }
+
+        if input::is_mouse_button_released(MouseButton::Left) {
+            if !mouse_in {
+                self.editing = None;
+            } else if self.editing.is_none() {
+                self.editing = Some(String::new());
+                return None;
+            }
+        }
+
+        let typed = self.editing.as_mut()?;
+        // Newest first
+        for c in chars.into_iter().rev() {
+            if c.is_ascii_digit() && typed.len() < 19 {
+                typed.push(c);
+            }
+        }
+        if input::is_key_pressed(KeyCode::Backspace) {
+            typed.pop();
+        }
+
+        if input::is_key_pressed(KeyCode::Escape) {
+            self.editing = None;
+        } else if input::is_key_pressed(KeyCode::Enter) || input::is_key_pressed(KeyCode::KpEnter) {
+            let seed = typed.parse().ok();
+            self.editing = None;
+            return seed;
+        }
+        None
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use crate::history::Pick;
+
+#[derive(Debug, Clone)]
+pub struct RecipeStats {
+    pub name: String,
+    pub offered: u32,
+    pub picked: u32,
+}
+
+impl RecipeStats {
+    pub fn pick_rate(&self) -> f32 {
+        if self.offered == 0 { 0.0 } else { self.picked as f32 / self.offered as f32 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    Name,
+    Offered,
+    Picked,
+    PickRate,
+}
+
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub recipes: Vec<RecipeStats>,
+    // (winner, loser) -> how many times the winner was picked while the loser was offered next to it
+    head_to_head: HashMap<(String, String), u32>,
+}
+
+impl Stats {
+    pub fn from_picks(picks: &[Pick]) -> Self {
+        let mut result = Stats::default();
+        for pick in picks {
+            for name in &pick.offered {
+                let index = match result.recipes.iter().position(|r| r.name == *name) {
+                    Some(i) => i,
+                    None => {
+                        result.recipes.push(RecipeStats { name: name.clone(), offered: 0, picked: 0 });
+                        result.recipes.len() - 1
+                    },
+                };
+                let recipe = &mut result.recipes[index];
+                recipe.offered += 1;
+                if *name == pick.chosen {
+                    recipe.picked += 1;
+                } else {
+                    *result.head_to_head.entry((pick.chosen.clone(), name.clone())).or_insert(0) += 1;
+                }
+            }
+        }
+        result
+    }
+
+    pub fn wins(&self, winner: &str, loser: &str) -> u32 {
+        self.head_to_head.get(&(winner.to_string(), loser.to_string())).copied().unwrap_or(0)
+    }
+
+    // Every recipe that met `name`, with (wins, losses) against it, most met first
+    pub fn matchups(&self, name: &str) -> Vec<(String, u32, u32)> {
+        let mut result: Vec<(String, u32, u32)> = Vec::new();
+        for (winner, loser) in self.head_to_head.keys() {
+            let opponent = if winner == name { loser } else if loser == name { winner } else { continue };
+            if result.iter().all(|m| m.0 != *opponent) {
+                result.push((opponent.clone(), self.wins(name, opponent), self.wins(opponent, name)));
+            }
+        }
+        result.sort_by(|a, b| (b.1 + b.2).cmp(&(a.1 + a.2)).then_with(|| a.0.cmp(&b.0)));
+        result
+    }
+
+    // Numbers sort biggest first, names alphabetically
+    pub fn sort(&mut self, by: SortBy) {
+        match by {
+            SortBy::Name => self.recipes.sort_by(|a, b| a.name.cmp(&b.name)),
+            SortBy::Offered => self.recipes.sort_by(|a, b| b.offered.cmp(&a.offered).then_with(|| a.name.cmp(&b.name))),
+            SortBy::Picked => self.recipes.sort_by(|a, b| b.picked.cmp(&a.picked).then_with(|| a.name.cmp(&b.name))),
+            SortBy::PickRate => self.recipes.sort_by(|a, b| b.pick_rate().total_cmp(&a.pick_rate()).then_with(|| a.name.cmp(&b.name))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pick(chosen: &str, offered: &[&str]) -> Pick {
+        Pick { timestamp: 0.0, seed: 1, draw: 1, offered: offered.iter().map(|n| n.to_string()).collect(), chosen: chosen.to_string() }
+    }
+
+    fn stats() -> Stats {
+        Stats::from_picks(&[
+            pick("Cast Screw", &["Cast Screw", "Iron Wire", "Steel Rod"]),
+            pick("Cast Screw", &["Iron Wire", "Cast Screw", "Bolted Frame"]),
+            pick("Iron Wire", &["Steel Rod", "Iron Wire", "Cast Screw"]),
+            pick("Bolted Frame", &["Bolted Frame", "Steel Rod"]),
+            pick("Iron Alloy Ingot", &["Iron Alloy Ingot", "Steel Rod"]),
+        ])
+    }
+
+    fn order(stats: &mut Stats, by: SortBy) -> Vec<&str> {
+        stats.sort(by);
+        stats.recipes.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    #[test]
+    fn counts() {
+        let stats = stats();
+        let screw = stats.recipes.iter().find(|r| r.name == "Cast Screw").unwrap();
+        assert_eq!((screw.offered, screw.picked), (3, 2));
+        assert!((screw.pick_rate() - 2.0 / 3.0).abs() < 1e-6);
+        assert_eq!(RecipeStats { name: String::new(), offered: 0, picked: 0 }.pick_rate(), 0.0);
+
+        assert_eq!(stats.wins("Cast Screw", "Iron Wire"), 2);
+        assert_eq!(stats.wins("Iron Wire", "Cast Screw"), 1);
+        // Losers of the same pick didn't meet each other
+        assert_eq!(stats.wins("Cast Screw", "Steel Rod") + stats.wins("Steel Rod", "Cast Screw"), 1);
+        // Most met first, then by name
+        assert_eq!(stats.matchups("Cast Screw"), vec![
+            ("Iron Wire".to_string(), 2, 1),
+            ("Bolted Frame".to_string(), 1, 0),
+            ("Steel Rod".to_string(), 1, 0),
+        ]);
+    }
+
+    #[test]
+    fn sorting() {
+        let mut stats = stats();
+        assert_eq!(order(&mut stats, SortBy::Name), vec!["Bolted Frame", "Cast Screw", "Iron Alloy Ingot", "Iron Wire", "Steel Rod"]);
+        assert_eq!(order(&mut stats, SortBy::Offered), vec!["Steel Rod", "Cast Screw", "Iron Wire", "Bolted Frame", "Iron Alloy Ingot"]);
+        assert_eq!(order(&mut stats, SortBy::Picked), vec!["Cast Screw", "Bolted Frame", "Iron Alloy Ingot", "Iron Wire", "Steel Rod"]);
+        assert_eq!(order(&mut stats, SortBy::PickRate), vec!["Iron Alloy Ingot", "Cast Screw", "Bolted Frame", "Iron Wire", "Steel Rod"]);
+    }
+}
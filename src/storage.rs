@@ -0,0 +1,43 @@
+// Persistent key/value storage: localStorage on the web build (see web.js), JSON files next to the game otherwise
+
+#[cfg(target_arch = "wasm32")]
+extern "C" {
+    fn storage_len(key: *const u8, key_len: usize) -> i32;
+    fn storage_read(key: *const u8, key_len: usize, buf: *mut u8, buf_len: usize);
+    fn storage_write(key: *const u8, key_len: usize, value: *const u8, value_len: usize);
+}
+
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+pub extern "C" fn storage_crate_version() -> u32 {
+    1 << 16
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load(key: &str) -> Option<String> {
+    let len = unsafe { storage_len(key.as_ptr(), key.len()) };
+    if len < 0 {
+        return None;
+    }
+    let mut buf = vec![0u8; len as usize];
+    unsafe { storage_read(key.as_ptr(), key.len(), buf.as_mut_ptr(), buf.len()) };
+    String::from_utf8(buf).ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save(key: &str, value: &str) {
+    unsafe { storage_write(key.as_ptr(), key.len(), value.as_ptr(), value.len()) };
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(key: &str) -> Option<String> {
+    std::fs::read_to_string(format!("{}.json", key)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(key: &str, value: &str) {
+    let path = format!("{}.json", key);
+    if let Err(e) = std::fs::write(&path, value) {
+        macroquad::logging::error!("Unable to save {}: {}", path, e);
+    }
+}
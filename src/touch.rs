@@ -0,0 +1,45 @@
+use macroquad::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Swipe {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+// Follows the first finger on the screen to tell swipes from taps.
+// Taps don't need it, macroquad turns them into mouse clicks.
+#[derive(Debug, Default)]
+pub struct SwipeTracker {
+    start: Option<(u64, Vec2)>,
+}
+
+impl SwipeTracker {
+    // Call once per frame, returns the swipe that ended this frame
+    pub fn update(&mut self) -> Option<Swipe> {
+        // A tenth of the smallest screen side
+        let min_distance = screen_width().min(screen_height()) * 0.1;
+        let mut result = None;
+        for touch in touches() {
+            match touch.phase {
+                TouchPhase::Started if self.start.is_none() => self.start = Some((touch.id, touch.position)),
+                TouchPhase::Ended | TouchPhase::Cancelled => {
+                    let Some((id, start)) = self.start else { continue };
+                    if id != touch.id {
+                        continue;
+                    }
+                    self.start = None;
+                    let delta = touch.position - start;
+                    if touch.phase == TouchPhase::Ended && delta.length() >= min_distance {
+                        result = Some(if delta.x.abs() > delta.y.abs() {
+                            if delta.x > 0.0 { Swipe::Right } else { Swipe::Left }
+                        } else if delta.y > 0.0 { Swipe::Down } else { Swipe::Up });
+                    }
+                },
+                _ => {},
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,115 @@
+// Sanity checks on the recipe data, reported at startup and enforced by a test
+
+use crate::items::*;
+
+// `image_exists` gets a path like res/images/Screw.png, the web build can't check it
+pub fn validate(recipes: &[Recipe], image_exists: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut result = Vec::new();
+    for (i, recipe) in recipes.iter().enumerate() {
+        if recipes[..i].iter().any(|r| r.name == recipe.name) {
+            result.push(format!("{}: duplicate recipe name", recipe.name));
+        }
+        if recipe.output.is_empty() {
+            result.push(format!("{}: no product", recipe.name));
+        }
+        if let Some(d) = recipe.duration.filter(|d| *d <= 0.0) {
+            result.push(format!("{}: duration should be positive, is {}", recipe.name, d));
+        }
+        for ingredient in recipe.input.iter().chain(&recipe.output) {
+            let item = ingredient.name.name();
+            if ingredient.nb <= 0.0 {
+                result.push(format!("{}: {} amount should be positive, is {}", recipe.name, item, ingredient.nb));
+            }
+            match &ingredient.name.info().icon {
+                Some(file) => {
+                    let path = format!("res/images/{}", file);
+                    if !image_exists(&path) {
+                        result.push(format!("{}: {} image {} is missing", recipe.name, item, path));
+                    }
+                },
+                None => result.push(format!("{}: {} has no image", recipe.name, item)),
+            }
+        }
+    }
+    result
+}
+
+// Same checks on the standard recipes, each one listed under the name of the item it makes
+pub fn validate_defaults(defaults: &DefaultRecipes, image_exists: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut keys: Vec<&String> = defaults.keys().collect();
+    keys.sort();
+    let mut result = Vec::new();
+    for key in keys {
+        let recipe = &defaults[key];
+        if let Some(product) = recipe.output.first().map(|o| o.name.name()).filter(|p| p != key) {
+            result.push(format!("default {}: makes {} instead", key, product));
+        }
+        result.extend(validate(std::slice::from_ref(recipe), &image_exists).into_iter().map(|w| format!("default {}", w)));
+    }
+    result
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn image_exists(path: &str) -> bool {
+    std::path::Path::new(path).exists()
+}
+
+// Files are fetched over http, a missing one only shows up when loading it
+#[cfg(target_arch = "wasm32")]
+pub fn image_exists(_path: &str) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recipes_json_is_valid() {
+        let recipes: Vec<Recipe> = serde_json::from_str(include_str!("../res/recipes.json")).unwrap();
+        let warnings = validate(&recipes, image_exists);
+        assert!(warnings.is_empty(), "{:#?}", warnings);
+
+        let defaults: DefaultRecipes = serde_json::from_str(include_str!("../res/default_recipes.json")).unwrap();
+        // Those two items have no icon in res/images yet, anything else is a new problem
+        let warnings = validate_defaults(&defaults, image_exists);
+        assert_eq!(warnings, vec![
+            "default Empty Gas Tank: Empty Gas Tank has no image",
+            "default Encased Plutonium Cell: Plutonium Pellet has no image",
+            "default Packaged Nitrogen Gas: Empty Gas Tank has no image",
+            "default Plutonium Pellet: Plutonium Pellet has no image",
+        ]);
+    }
+
+    #[test]
+    fn reports_default_problems() {
+        let defaults = DefaultRecipes::from([
+            ("Screw".to_string(), Recipe::test("Screw", &[("Iron Rod", 10.0)], &[("Screw", 40.0)])),
+            ("Wire".to_string(), Recipe::test("Wire", &[("Copper Ingot", 15.0)], &[("Cable", 30.0)])),
+            ("Plastic".to_string(), Recipe::test("Plastic", &[("Crude Oil", 30.0)], &[])),
+        ]);
+        assert_eq!(validate_defaults(&defaults, |_| true), vec![
+            "default Plastic: no product",
+            "default Wire: makes Cable instead",
+        ]);
+    }
+
+    #[test]
+    fn reports_problems() {
+        let recipes = vec![
+            Recipe::test("Cast Screw", &[("Iron Ingot", 12.5)], &[("Screw", 50.0)]),
+            Recipe::test("Cast Screw", &[("Iron Ingot", 0.0)], &[("Screw", 50.0)]),
+            Recipe::test("Mystery", &[("Plutonium Pellet", 1.0)], &[("Screw", -1.0)]),
+        ];
+        let warnings = validate(&recipes, |p| p != "res/images/Screw.png");
+        assert_eq!(warnings, vec![
+            "Cast Screw: Screw image res/images/Screw.png is missing",
+            "Cast Screw: duplicate recipe name",
+            "Cast Screw: Iron Ingot amount should be positive, is 0",
+            "Cast Screw: Screw image res/images/Screw.png is missing",
+            "Mystery: Plutonium Pellet has no image",
+            "Mystery: Screw amount should be positive, is -1",
+            "Mystery: Screw image res/images/Screw.png is missing",
+        ]);
+    }
+}
@@ -0,0 +1,66 @@
+// Page URL access for the web build, the JS side lives in web.js
+
+// Where the web build is hosted, used for links copied from native builds
+#[cfg(not(target_arch = "wasm32"))]
+const PAGE_URL: &str = "https://sl3dge78.github.io/Satisfactory_AltRecipe/";
+
+#[cfg(target_arch = "wasm32")]
+extern "C" {
+    fn url_param_len(name: *const u8, name_len: usize) -> i32;
+    fn url_param_read(name: *const u8, name_len: usize, buf: *mut u8, buf_len: usize);
+    fn url_param_set(name: *const u8, name_len: usize, value: *const u8, value_len: usize);
+    fn url_copy();
+}
+
+// gl.js checks each plugin of web.js against the <name>_crate_version export of the same name, here 0.1.0
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+pub extern "C" fn url_params_crate_version() -> u32 {
+    1 << 16
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn url_param(name: &str) -> Option<String> {
+    let len = unsafe { url_param_len(name.as_ptr(), name.len()) };
+    if len < 0 {
+        return None;
+    }
+    let mut buf = vec![0u8; len as usize];
+    unsafe { url_param_read(name.as_ptr(), name.len(), buf.as_mut_ptr(), buf.len()) };
+    String::from_utf8(buf).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn url_param(_name: &str) -> Option<String> {
+    None
+}
+
+// Puts the params in the address bar and copies the resulting link
+#[cfg(target_arch = "wasm32")]
+pub fn copy_link(params: &[(&str, String)]) {
+    for (name, value) in params {
+        unsafe { url_param_set(name.as_ptr(), name.len(), value.as_ptr(), value.len()) };
+    }
+    unsafe { url_copy() };
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn copy_link(params: &[(&str, String)]) {
+    let query: Vec<String> = params.iter().map(|(name, value)| format!("{}={}", name, encode(value))).collect();
+    let link = format!("{}?{}", PAGE_URL, query.join("&"));
+    let gl = unsafe { macroquad::window::get_internal_gl() };
+    gl.quad_context.clipboard_set(&link);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn encode(value: &str) -> String {
+    let mut result = String::new();
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || b"-_.~,".contains(&b) {
+            result.push(b as char);
+        } else {
+            result.push_str(&format!("%{:02X}", b));
+        }
+    }
+    result
+}
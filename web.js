@@ -0,0 +1,100 @@
+// Gives the game access to the page URL, localStorage and gamepads, see src/web.rs, src/storage.rs and src/gamepad.rs
+// Must be loaded after gl.js and before load() is called
+
+var url_params_plugin = {
+    name: "url_params",
+    version: "0.1.0",
+    register_plugin: function (importObject) {
+        var url = new URL(window.location.href);
+
+        importObject.env.url_param_len = function (name_ptr, name_len) {
+            var value = url.searchParams.get(UTF8ToString(name_ptr, name_len));
+            if (value == null) {
+                return -1;
+            }
+            return new TextEncoder().encode(value).length;
+        };
+
+        importObject.env.url_param_read = function (name_ptr, name_len, buf_ptr, buf_len) {
+            var value = url.searchParams.get(UTF8ToString(name_ptr, name_len));
+            if (value != null) {
+                var heap = new Uint8Array(wasm_memory.buffer, buf_ptr, buf_len);
+                heap.set(new TextEncoder().encode(value).subarray(0, buf_len));
+            }
+        };
+
+        importObject.env.url_param_set = function (name_ptr, name_len, value_ptr, value_len) {
+            url.searchParams.set(UTF8ToString(name_ptr, name_len), UTF8ToString(value_ptr, value_len));
+            window.history.replaceState(null, "", url.href);
+        };
+
+        importObject.env.url_copy = function () {
+            if (navigator.clipboard != undefined) {
+                navigator.clipboard.writeText(url.href).catch(function (err) {
+                    console.error("Unable to copy link: " + err);
+                });
+            } else {
+                console.warn("Clipboard unavailable, link is: " + url.href);
+            }
+        };
+    },
+};
+
+var storage_plugin = {
+    name: "storage",
+    version: "0.1.0",
+    register_plugin: function (importObject) {
+        var prefix = "satisfactory_alt_recipe.";
+
+        importObject.env.storage_len = function (key_ptr, key_len) {
+            var value = window.localStorage.getItem(prefix + UTF8ToString(key_ptr, key_len));
+            if (value == null) {
+                return -1;
+            }
+            return new TextEncoder().encode(value).length;
+        };
+
+        importObject.env.storage_read = function (key_ptr, key_len, buf_ptr, buf_len) {
+            var value = window.localStorage.getItem(prefix + UTF8ToString(key_ptr, key_len));
+            if (value != null) {
+                var heap = new Uint8Array(wasm_memory.buffer, buf_ptr, buf_len);
+                heap.set(new TextEncoder().encode(value).subarray(0, buf_len));
+            }
+        };
+
+        importObject.env.storage_write = function (key_ptr, key_len, value_ptr, value_len) {
+            try {
+                window.localStorage.setItem(prefix + UTF8ToString(key_ptr, key_len), UTF8ToString(value_ptr, value_len));
+            } catch (err) {
+                console.error("Unable to save: " + err);
+            }
+        };
+    },
+};
+
+var gamepad_plugin = {
+    name: "gamepad",
+    version: "0.1.0",
+    register_plugin: function (importObject) {
+        importObject.env.gamepad_buttons = function () {
+            var mask = 0;
+            var pads = navigator.getGamepads ? navigator.getGamepads() : [];
+            for (var i = 0; i < pads.length; i++) {
+                if (pads[i] == null || !pads[i].connected) {
+                    continue;
+                }
+                var buttons = pads[i].buttons;
+                for (var b = 0; b < buttons.length && b < 32; b++) {
+                    if (buttons[b].pressed) {
+                        mask |= 1 << b;
+                    }
+                }
+            }
+            return mask;
+        };
+    },
+};
+
+miniquad_add_plugin(url_params_plugin);
+miniquad_add_plugin(storage_plugin);
+miniquad_add_plugin(gamepad_plugin);